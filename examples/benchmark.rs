@@ -40,9 +40,9 @@ pub fn do_ecf_benchmark() -> Duration {
 	let (mut ecf_file, _did_run_updaters, _errors) = ecf::File::from_str(include_str!("example_settings.ecf"), &[], &mut ());
 	
 	// query and edit settings
-	ecf_file.add_missing_values([
-		("This key must exist, and the default (if missing) is Value::I64(64)", ecf::Value::I64(64)),
-	].into_iter());
+	let schema = ecf::Schema::new()
+		.entry(ecf::SchemaEntry::new("This key must exist, and the default (if missing) is Value::I64(64)", ecf::ValueType::Int).default(64));
+	schema.apply_defaults(&mut ecf_file);
 	let _example_value = black_box(ecf_file.get_str("example key"));
 	let _example_blank = black_box(ecf_file.get_empty("example blank"));
 	let _example_int = black_box(ecf_file.get_int("example int"));
@@ -64,12 +64,7 @@ pub fn do_toml_benchmark() -> Duration {
 	let mut settings = include_str!("example_settings.toml").parse::<Table>().unwrap();
 	
 	// query and edit settings
-	for kv in &[
-		("This key must exist, and the default (if missing) is Value::I64(64)", toml::Value::Integer(64))
-	] {
-		let entry = settings.entry(kv.0);
-		entry.or_insert(kv.1.clone());
-	}
+	settings.entry("This key must exist, and the default (if missing) is Value::I64(64)").or_insert(toml::Value::Integer(64));
 	let _example_value = black_box(settings.get("example key"));
 	let _example_blank = black_box(settings.get("example blank"));
 	let _example_int = black_box(settings.get("example int"));
@@ -1,7 +1,6 @@
 // to run: `cargo run --example main`
 
 use easy_configuration_format as ecf; // recommended way to import functionality
-use std::collections::HashMap;
 
 
 
@@ -11,23 +10,25 @@ fn main() {
 	
 	// load (and update) settings
 	
-	pub struct UpdaterFunctionArgs {}
-	pub const UPDATER_FUNCTIONS: &[fn(&mut HashMap<String, ecf::Value>, &mut UpdaterFunctionArgs)] = &[
-		update_1_to_2, // updates from format 1 to format 2
+	pub struct MigrationArgs {}
+	pub const MIGRATIONS: &[&dyn ecf::Migration<MigrationArgs>] = &[
+		&migrate_1_to_2, // migrates from format 1 to format 2
 		// etc
-	]; // because there's 1 updater function, the crate will know that the newest format version is 2
+	]; // because there's 1 migration, the crate will know that the newest format version is 2
 	
-	pub fn update_1_to_2(_settings: &mut HashMap<String, ecf::Value>, _args: &mut UpdaterFunctionArgs) {
-		println!("this example doesn't actually have a format 2, this is just to show how updates would be done");
+	pub fn migrate_1_to_2(_settings: &mut ecf::File, _context: &mut ecf::MigrationContext<MigrationArgs>) -> Result<(), ecf::MigrationError> {
+		println!("this example doesn't actually have a format 2, this is just to show how migrations would be done");
+		Ok(()) // if something goes wrong, return `Err(context.error("..."))` and every change will be rolled back
 	}
 	
-	let mut update_args = UpdaterFunctionArgs {};
-	let (mut ecf_file, _did_run_updaters, errors) = ecf::File::from_str(include_str!("example_settings.ecf"), UPDATER_FUNCTIONS, &mut update_args); // NOTE: if you want to completely skip updater functions, you can replace `UPDATER_FUNCTIONS` with `&[]`
+	let mut migration_args = MigrationArgs {};
+	let (mut ecf_file, migration_result, errors) = ecf::File::from_str_with_migrations(include_str!("example_settings.ecf"), MIGRATIONS, &mut migration_args); // NOTE: if you want to completely skip migrations, you can replace `MIGRATIONS` with `&[]`
+	if let Err(err) = migration_result {println!("{err}");}
 	
 	// if the user removes necessary settings, this can add them back
-	ecf_file.add_missing_values([
-		("This key must exist, and the default (if missing) is Value::I64(64)", ecf::Value::I64(64)),
-	].into_iter());
+	let schema = ecf::Schema::new()
+		.entry(ecf::SchemaEntry::new("This key must exist, and the default (if missing) is Value::I64(64)", ecf::ValueType::Int).default(64));
+	schema.apply_defaults(&mut ecf_file);
 	
 	
 	
@@ -0,0 +1,125 @@
+use crate::*;
+
+
+
+/// Lightweight rename of a setting, which can be used instead of an updater function for simple renames
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KeyAlias {
+	/// The old key
+	pub old: String,
+	/// The new key
+	pub new: String,
+	/// Optional message explaining the rename, which is included in the warning
+	pub deprecation: Option<String>,
+}
+
+impl KeyAlias {
+	/// Creates an alias with no deprecation message
+	pub fn new(old: impl Into<String>, new: impl Into<String>) -> Self {
+		Self {
+			old: old.into(),
+			new: new.into(),
+			deprecation: None,
+		}
+	}
+	/// Sets the deprecation message
+	pub fn deprecated(mut self, message: impl Into<String>) -> Self {
+		self.deprecation = Some(message.into());
+		self
+	}
+}
+
+
+
+/// Record of an old key being found by `File::apply_aliases()` (or while loading with `File::from_str_with_aliases()` / `File::load_with_aliases()`)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AliasWarning {
+	/// The old key that was found
+	pub old: String,
+	/// The new key that the setting was renamed to
+	pub new: String,
+	/// Line number of the setting (using 1-based indexing), or `None` if the setting isn't in the file's layout
+	pub line: Option<usize>,
+	/// True if the new key was already defined, in which case the old setting was commented out instead of renamed
+	pub conflict: bool,
+	/// The alias's deprecation message
+	pub deprecation: Option<String>,
+}
+
+impl std::fmt::Display for AliasWarning {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Setting '{}'", self.old)?;
+		if let Some(line) = self.line {
+			write!(f, " at line {line}")?;
+		}
+		if self.conflict {
+			write!(f, " has been renamed to '{}', which is already defined, so the old setting was commented out", self.new)?;
+		} else {
+			write!(f, " has been renamed to '{}'", self.new)?;
+		}
+		if let Some(deprecation) = &self.deprecation {
+			write!(f, ": {deprecation}")?;
+		}
+		Ok(())
+	}
+}
+
+
+
+impl File {
+	
+	/// Same as `from_str()`, but also applies aliases once the updaters have run (see `apply_aliases()`), so old keys are renamed as the file is loaded
+	pub fn from_str_with_aliases<T>(contents: impl AsRef<str>, updater_fns: &[UpdaterFn<T>], args: &mut T, aliases: &[KeyAlias]) -> (Self, DidRunUpdaters, Vec<ParseEntryError>, Vec<AliasWarning>) {
+		let (mut file, did_run_updaters, errors) = Self::from_str(contents, updater_fns, args);
+		let warnings = file.apply_aliases(aliases.iter().cloned());
+		(file, did_run_updaters, errors, warnings)
+	}
+	
+	/// Renames every setting that uses an old key (in both `values` and `layout`), and remembers the aliases so that the getters can resolve old keys
+	/// 
+	/// Aliases are applied in order, so chained renames (`a` -> `b`, then `b` -> `c`) work as expected. If both the old and the new key are defined, the new key's value is kept and the old setting is commented out
+	pub fn apply_aliases(&mut self, aliases: impl IntoIterator<Item = KeyAlias>) -> Vec<AliasWarning> {
+		let mut warnings = vec!();
+		for alias in aliases {
+			if let Some(value) = self.values.remove(&alias.old) {
+				let conflict = self.values.contains_key(&alias.new);
+				let layout_index = self.layout_index(&alias.old);
+				let line = layout_index.map(|layout_index| self.layout_line_numbers()[layout_index]);
+				if conflict {
+					let comment = LayoutEntry::Comment (format!(" {}: {} (replaced by \"{}\")", alias.old, value.format().trim_end(), alias.new));
+					match layout_index {
+						Some(layout_index) => self.layout[layout_index] = comment,
+						None => self.layout.push(comment),
+					}
+				} else {
+					if let Some(layout_index) = layout_index {
+						self.layout[layout_index] = LayoutEntry::Key (alias.new.clone());
+					}
+					self.values.insert(alias.new.clone(), value);
+				}
+				warnings.push(AliasWarning {
+					old: alias.old.clone(),
+					new: alias.new.clone(),
+					line,
+					conflict,
+					deprecation: alias.deprecation.clone(),
+				});
+			}
+			self.aliases.retain(|other| other.old != alias.old);
+			self.aliases.push(alias);
+		}
+		warnings
+	}
+	
+	/// Returns the key that a setting is actually stored under, following aliases if `key` isn't defined
+	pub fn resolve_key<'a>(&'a self, key: &'a str) -> &'a str {
+		let mut key = key;
+		for _ in 0..self.aliases.len() {
+			if self.values.contains_key(key) {break;}
+			let Some(alias) = self.aliases.iter().find(|alias| alias.old == key) else {break;};
+			key = &alias.new;
+		}
+		key
+	}
+	
+}
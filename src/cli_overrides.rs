@@ -0,0 +1,94 @@
+use crate::*;
+use std::path::{Path, PathBuf};
+
+
+
+/// Reads settings overrides from command-line arguments, without depending on any argument-parsing crate
+/// 
+/// These arguments are recognized:
+/// - `--set key=value` or `--set=key=value`, which can be repeated, where `value` uses the same rules as values in ecf files (see `Value::parse()`)
+/// - `--set key=@path`, which sets `key` to the contents of a file as a string (this is the easiest way to pass multiline strings)
+/// - `--config path` or `--config=path`, which can be repeated, where the paths are only collected and loading them is left to the program
+/// 
+/// Every other argument is kept in `remaining` so the program can handle it, along with everything after `--`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CliOverrides {
+	/// Every setting given with `--set`, in order (and meant to be used as an override layer, either by adding it to a `LayeredConfig` or by passing it to `File::apply_overrides()`)
+	pub overrides: File,
+	/// Every path given with `--config`, in order
+	pub config_paths: Vec<PathBuf>,
+	/// Every argument that wasn't recognized
+	pub remaining: Vec<String>,
+}
+
+impl CliOverrides {
+	
+	/// Parses arguments (not including the program's name, so usually `std::env::args().skip(1)`), reading `@path` values from disk
+	pub fn parse(args: impl IntoIterator<Item = impl Into<String>>) -> (Self, Vec<CliArgError>) {
+		Self::parse_with_reader(args, |path| std::fs::read_to_string(path))
+	}
+	
+	/// Same as `parse()`, but uses `read_file` to read `@path` values (which is mainly useful for testing)
+	pub fn parse_with_reader(args: impl IntoIterator<Item = impl Into<String>>, mut read_file: impl FnMut(&Path) -> std::io::Result<String>) -> (Self, Vec<CliArgError>) {
+		let mut output = Self {
+			overrides: File::new(0),
+			config_paths: vec!(),
+			remaining: vec!(),
+		};
+		let mut errors = vec!();
+		let mut args = args.into_iter().map(Into::into);
+		while let Some(arg) = args.next() {
+			if arg == "--" {
+				output.remaining.push(arg);
+				output.remaining.extend(args.by_ref());
+				break;
+			}
+			let (flag, inline_value) = match arg.split_once('=') {
+				Some((flag, value)) if flag == "--set" || flag == "--config" => (flag.to_string(), Some(value.to_string())),
+				_ => (arg.clone(), None),
+			};
+			if flag != "--set" && flag != "--config" {
+				output.remaining.push(arg);
+				continue;
+			}
+			let Some(value) = inline_value.or_else(|| args.next()) else {
+				errors.push(CliArgError::new(&arg, format!("Expected a value after {flag}")));
+				continue;
+			};
+			let full_arg = if arg == flag {format!("{flag} {value}")} else {arg};
+			if flag == "--config" {
+				output.config_paths.push(PathBuf::from(value));
+				continue;
+			}
+			match parse_override(&value, &mut read_file) {
+				Ok((key, value)) => {output.overrides.set(key, value);}
+				Err(message) => errors.push(CliArgError::new(full_arg, message)),
+			}
+		}
+		(output, errors)
+	}
+	
+	/// Sets every overridden setting in `file`
+	pub fn apply(&self, file: &mut File) {
+		file.apply_overrides(&self.overrides);
+	}
+	
+}
+
+
+
+/// Parses the `key=value` part of a `--set` argument
+fn parse_override(text: &str, read_file: &mut impl FnMut(&Path) -> std::io::Result<String>) -> Result<(String, Value), String> {
+	let Some((key, value)) = text.split_once('=') else {return Err(String::from("Expected \"key=value\""));};
+	let key = key.trim();
+	if key.is_empty() {return Err(String::from("Key cannot be empty"));}
+	if key.contains(':') {return Err(String::from("Keys cannot contain colons"));}
+	if let Some(path) = value.trim().strip_prefix('@') {
+		let contents = read_file(Path::new(path)).map_err(|err| format!("Could not read file \"{path}\": {err}"))?;
+		let contents = contents.strip_suffix('\n').unwrap_or(&contents);
+		let contents = contents.strip_suffix('\r').unwrap_or(contents);
+		return Ok((key.to_string(), Value::String (contents.to_string())));
+	}
+	let value = Value::parse(value)?;
+	Ok((key.to_string(), value))
+}
@@ -0,0 +1,97 @@
+use crate::*;
+use std::sync::Mutex;
+
+
+
+/// Settings for converting user-written values into the type that they're retrieved as (like `volume: "75"` being retrieved as an int)
+/// 
+/// Every coercion that happens is recorded, and can be retrieved with `File::coercion_warnings()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Coercion {
+	/// Allows quoted numbers (like `"75"` or `"0.5"`) to be retrieved as ints or floats
+	pub quoted_numbers: bool,
+	/// Allows `"yes"`, `"no"`, `"on"`, `"off"`, `"true"`, `"false"`, `"1"`, `"0"`, `1`, and `0` to be retrieved as bools
+	pub bool_words: bool,
+	/// Allows ints to be retrieved as floats
+	pub int_to_float: bool,
+	/// If true, `File::to_str()` writes coerced values with their correct type instead of how they were written
+	pub rewrite_on_save: bool,
+}
+
+impl Coercion {
+	/// No coercion, every value has to be exactly the type that it's retrieved as (this is the default)
+	pub const STRICT: Self = Self {
+		quoted_numbers: false,
+		bool_words: false,
+		int_to_float: false,
+		rewrite_on_save: false,
+	};
+	/// Every type of coercion, without rewriting values when saving
+	pub const LENIENT: Self = Self {
+		quoted_numbers: true,
+		bool_words: true,
+		int_to_float: true,
+		rewrite_on_save: false,
+	};
+	/// Tries to convert a value into the given type ("Int", "Float", or "Bool", same as `Value::type_as_string()`)
+	pub fn coerce(&self, value: &Value, expected: &str) -> Option<Value> {
+		match (expected, value) {
+			("Int", Value::String (string)) if self.quoted_numbers => string.trim().parse::<i64>().ok().map(Value::I64),
+			("Float", Value::String (string)) if self.quoted_numbers => string.trim().parse::<f64>().ok().map(Value::F64),
+			("Float", Value::I64 (int)) if self.int_to_float => Some(Value::F64 (*int as f64)),
+			("Bool", Value::String (string)) if self.bool_words => match &*string.trim().to_lowercase() {
+				"yes" | "on" | "true" | "1" => Some(Value::Bool (true)),
+				"no" | "off" | "false" | "0" => Some(Value::Bool (false)),
+				_ => None,
+			},
+			("Bool", Value::I64 (1)) if self.bool_words => Some(Value::Bool (true)),
+			("Bool", Value::I64 (0)) if self.bool_words => Some(Value::Bool (false)),
+			_ => None,
+		}
+	}
+}
+
+
+
+/// Record of a value being coerced into a different type
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoercionWarning {
+	/// The key that was queried
+	pub key: String,
+	/// The value as it's stored in the file
+	pub original: Value,
+	/// The value that was actually returned
+	pub coerced: Value,
+}
+
+impl std::fmt::Display for CoercionWarning {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Setting '{}' was converted from {} to {}", self.key, self.original.format(), self.coerced.format())
+	}
+}
+
+
+
+/// Interior-mutable list of coercions, so that `&self` getters can still record them
+#[derive(Debug, Default)]
+pub(crate) struct CoercionLog (Mutex<Vec<CoercionWarning>>);
+
+impl CoercionLog {
+	pub(crate) fn record(&self, warning: CoercionWarning) {
+		let mut warnings = self.0.lock().unwrap_or_else(|err| err.into_inner());
+		if warnings.contains(&warning) {return;}
+		warnings.push(warning);
+	}
+	pub(crate) fn get_all(&self) -> Vec<CoercionWarning> {
+		self.0.lock().unwrap_or_else(|err| err.into_inner()).clone()
+	}
+	pub(crate) fn clear(&self) {
+		self.0.lock().unwrap_or_else(|err| err.into_inner()).clear();
+	}
+}
+
+impl Clone for CoercionLog {
+	fn clone(&self) -> Self {
+		Self (Mutex::new(self.get_all()))
+	}
+}
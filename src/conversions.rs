@@ -0,0 +1,314 @@
+use crate::*;
+use std::path::PathBuf;
+
+
+
+/// Used for `File::get()`, converts setting values into rust types
+pub trait FromValue: Sized {
+	
+	/// Converts a single value, `key` is only used for error messages
+	fn from_value(key: &str, value: &Value) -> Result<Self, RetrieveSettingError>;
+	
+	/// Retrieves the value from a file, which by default just looks up `key` and calls `from_value()`
+	/// 
+	/// This can be overridden for types that are made up of multiple settings (like `Vec<T>`, which is stored as an array namespace)
+	fn from_file(file: &File, key: &str) -> Result<Self, RetrieveSettingError> {
+		match file.values.get(key) {
+			None => Err(RetrieveSettingError::new_missing(key)),
+			Some(value) => Self::from_value(key, value),
+		}
+	}
+	
+}
+
+
+
+/// Used for `ecf::value()` overloading, converts rust types into setting values
+/// 
+/// This is only implemented for types that can be converted without losing data, so types like `u64` and `usize` need to be converted to `i64` first (for example, with `i64::try_from()`)
+pub trait IntoValue {
+	/// Purpose of trait
+	fn into_value(self) -> Value;
+}
+
+
+
+impl FromValue for Value {
+	fn from_value(_key: &str, value: &Value) -> Result<Self, RetrieveSettingError> {
+		Ok(value.clone())
+	}
+}
+
+impl IntoValue for Value {
+	fn into_value(self) -> Value {
+		self
+	}
+}
+
+
+
+macro_rules! impl_int_conversions {
+	($impl_type:ty) => {
+		impl FromValue for $impl_type {
+			fn from_value(key: &str, value: &Value) -> Result<Self, RetrieveSettingError> {
+				match value {
+					Value::I64 (v) => <$impl_type>::try_from(*v).map_err(|_| RetrieveSettingError::new_out_of_range(key, v.to_string(), stringify!($impl_type))),
+					_ => Err(RetrieveSettingError::new_wrong_singular_type(key, "Int", value.type_as_string())),
+				}
+			}
+		}
+	};
+	($impl_type:ty, lossless) => {
+		impl_int_conversions!($impl_type);
+		impl IntoValue for $impl_type {
+			fn into_value(self) -> Value {
+				Value::I64 (i64::from(self))
+			}
+		}
+	};
+}
+
+impl_int_conversions!(i8, lossless);
+impl_int_conversions!(u8, lossless);
+impl_int_conversions!(i16, lossless);
+impl_int_conversions!(u16, lossless);
+impl_int_conversions!(i32, lossless);
+impl_int_conversions!(u32, lossless);
+impl_int_conversions!(i64, lossless);
+impl_int_conversions!(u64);
+impl_int_conversions!(i128);
+impl_int_conversions!(u128);
+impl_int_conversions!(isize);
+impl_int_conversions!(usize);
+
+
+
+impl FromValue for f64 {
+	fn from_value(key: &str, value: &Value) -> Result<Self, RetrieveSettingError> {
+		match value {
+			Value::F64 (v) => Ok(*v),
+			_ => Err(RetrieveSettingError::new_wrong_singular_type(key, "Float", value.type_as_string())),
+		}
+	}
+}
+
+impl IntoValue for f64 {
+	fn into_value(self) -> Value {
+		Value::F64 (self)
+	}
+}
+
+impl FromValue for f32 {
+	fn from_value(key: &str, value: &Value) -> Result<Self, RetrieveSettingError> {
+		match value {
+			Value::F64 (v) if v.is_finite() && v.abs() > f32::MAX as f64 => Err(RetrieveSettingError::new_out_of_range(key, v.to_string(), "f32")),
+			Value::F64 (v) => Ok(*v as f32),
+			_ => Err(RetrieveSettingError::new_wrong_singular_type(key, "Float", value.type_as_string())),
+		}
+	}
+}
+
+impl IntoValue for f32 {
+	fn into_value(self) -> Value {
+		Value::F64 (self as f64)
+	}
+}
+
+
+
+impl FromValue for bool {
+	fn from_value(key: &str, value: &Value) -> Result<Self, RetrieveSettingError> {
+		match value {
+			Value::Bool (v) => Ok(*v),
+			_ => Err(RetrieveSettingError::new_wrong_singular_type(key, "Bool", value.type_as_string())),
+		}
+	}
+}
+
+impl IntoValue for bool {
+	fn into_value(self) -> Value {
+		Value::Bool (self)
+	}
+}
+
+
+
+impl FromValue for String {
+	fn from_value(key: &str, value: &Value) -> Result<Self, RetrieveSettingError> {
+		match value {
+			Value::String (v) => Ok(v.clone()),
+			_ => Err(RetrieveSettingError::new_wrong_singular_type(key, "String", value.type_as_string())),
+		}
+	}
+}
+
+impl IntoValue for String {
+	fn into_value(self) -> Value {
+		Value::String (self)
+	}
+}
+
+impl IntoValue for &str {
+	fn into_value(self) -> Value {
+		Value::String (self.to_string())
+	}
+}
+
+impl FromValue for PathBuf {
+	fn from_value(key: &str, value: &Value) -> Result<Self, RetrieveSettingError> {
+		String::from_value(key, value).map(PathBuf::from)
+	}
+}
+
+impl FromValue for char {
+	fn from_value(key: &str, value: &Value) -> Result<Self, RetrieveSettingError> {
+		let string = String::from_value(key, value)?;
+		let mut chars = string.chars();
+		match (chars.next(), chars.next()) {
+			(Some(c), None) => Ok(c),
+			_ => Err(RetrieveSettingError::new_out_of_range(key, value.format(), "char")),
+		}
+	}
+}
+
+impl IntoValue for char {
+	fn into_value(self) -> Value {
+		Value::String (self.to_string())
+	}
+}
+
+
+
+/// `Empty` values are converted to `None`, but missing keys are still an error
+impl<T: FromValue> FromValue for Option<T> {
+	fn from_value(key: &str, value: &Value) -> Result<Self, RetrieveSettingError> {
+		match value {
+			Value::Empty => Ok(None),
+			_ => T::from_value(key, value).map(Some),
+		}
+	}
+	fn from_file(file: &File, key: &str) -> Result<Self, RetrieveSettingError> {
+		match file.values.get(key) {
+			Some(Value::Empty) => Ok(None),
+			_ => T::from_file(file, key).map(Some),
+		}
+	}
+}
+
+/// `None` is converted to `Empty`
+impl<T: IntoValue> IntoValue for Option<T> {
+	fn into_value(self) -> Value {
+		match self {
+			Some(v) => v.into_value(),
+			None => Value::Empty,
+		}
+	}
+}
+
+
+
+/// Vecs are stored as array namespaces (`key.0`, `key.1`, etc), and an `Empty` value at `key` itself is treated as an empty vec
+impl<T: FromValue> FromValue for Vec<T> {
+	fn from_value(key: &str, value: &Value) -> Result<Self, RetrieveSettingError> {
+		match value {
+			Value::Empty => Ok(vec!()),
+			_ => Err(RetrieveSettingError::new_wrong_singular_type(key, "Array", value.type_as_string())),
+		}
+	}
+	fn from_file(file: &File, key: &str) -> Result<Self, RetrieveSettingError> {
+		if let Some(value) = file.values.get(key) {
+			return Self::from_value(key, value);
+		}
+		let prefix = format!("{key}.");
+		let len =
+			file.values.keys()
+			.filter_map(|other_key| other_key.strip_prefix(&prefix))
+			.filter_map(|index| index.split('.').next()?.parse::<usize>().ok())
+			.max()
+			.map(|max_index| max_index + 1);
+		let Some(len) = len else {return Err(RetrieveSettingError::new_missing(key));};
+		(0..len)
+			.map(|i| T::from_file(file, &format!("{key}.{i}")))
+			.collect()
+	}
+}
+
+
+
+/// Used for `File::get_enum()`, describes an enum that is stored as a string setting
+/// 
+/// This can be implemented by hand, but the easiest way is to use the `ecf_enum!()` macro
+pub trait EcfEnum: Sized {
+	
+	/// Every accepted spelling, including aliases
+	const SPELLINGS: &'static [&'static str];
+	
+	/// Returns the variant for a spelling, or `None` if the spelling isn't accepted
+	fn from_spelling(spelling: &str) -> Option<Self>;
+	
+	/// Returns the canonical spelling of a variant, which is what gets written when the variant is stored as a value
+	fn spelling(&self) -> &'static str;
+	
+	/// Converts a single value, `key` is only used for error messages
+	fn from_setting(key: &str, value: &Value) -> Result<Self, RetrieveSettingError> {
+		let string = String::from_value(key, value)?;
+		Self::from_spelling(&string).ok_or_else(|| {
+			let closest = crate::utils::closest_typo_match(&string, Self::SPELLINGS.iter().copied());
+			RetrieveSettingError::new_invalid_variant(key, string.clone(), Self::SPELLINGS, closest)
+		})
+	}
+	
+}
+
+
+
+/// Defines an enum along with implementations for `EcfEnum`, `FromValue`, and `IntoValue`
+/// 
+/// Each variant lists its canonical spelling first, followed by any aliases:
+/// 
+/// ```
+/// easy_configuration_format::ecf_enum! {
+///     #[derive(Debug, Clone, Copy, PartialEq)]
+///     pub enum LogLevel {
+///         Debug = "debug" | "verbose",
+///         Info = "info",
+///         Error = "error",
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! ecf_enum {
+	(
+		$(#[$attr:meta])*
+		$vis:vis enum $name:ident {
+			$($(#[$variant_attr:meta])* $variant:ident = $spelling:literal $(| $alias:literal)*),* $(,)?
+		}
+	) => {
+		$(#[$attr])*
+		$vis enum $name {
+			$($(#[$variant_attr])* $variant,)*
+		}
+		impl $crate::EcfEnum for $name {
+			const SPELLINGS: &'static [&'static str] = &[$($spelling, $($alias,)*)*];
+			fn from_spelling(spelling: &str) -> Option<Self> {
+				$(if spelling == $spelling $(|| spelling == $alias)* {return Some(Self::$variant);})*
+				None
+			}
+			fn spelling(&self) -> &'static str {
+				match self {
+					$(Self::$variant => $spelling,)*
+				}
+			}
+		}
+		impl $crate::FromValue for $name {
+			fn from_value(key: &str, value: &$crate::Value) -> Result<Self, $crate::RetrieveSettingError> {
+				<Self as $crate::EcfEnum>::from_setting(key, value)
+			}
+		}
+		impl $crate::IntoValue for $name {
+			fn into_value(self) -> $crate::Value {
+				$crate::Value::String ($crate::EcfEnum::spelling(&self).to_string())
+			}
+		}
+	};
+}
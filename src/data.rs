@@ -1,5 +1,12 @@
+use std::collections::HashMap;
+
+
+
 /// Describes the layout of a loaded settings file line-by-line
+/// 
+/// More kinds of entries may be added in the future, so matches on this need a wildcard arm
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
 pub enum LayoutEntry {
 	/// Empty line
 	Empty,
@@ -7,6 +14,8 @@ pub enum LayoutEntry {
 	Key (String),
 	/// Comment
 	Comment (String),
+	/// Include directive, holding the included path or pattern (these are only parsed when loading with `FileSet::load_with_includes()`)
+	Include (String),
 }
 
 
@@ -50,6 +59,12 @@ impl Value {
 			}
 		}
 	}
+	/// Opposite of `format()`, parses a value using the same rules as values in ecf files (so `empty`, `true`, `800`, `-1.5`, `"text"`, and multiline strings are all accepted)
+	/// 
+	/// The error is the reason that the text is invalid
+	pub fn parse(text: impl AsRef<str>) -> Result<Self, String> {
+		crate::file::parse_value_text(text.as_ref())
+	}
 	/// Returns "Empty", "String", "Int", "Float", or "Bool" according to enum state
 	pub const fn type_as_string(&self) -> &'static str {
 		match self {
@@ -76,3 +91,8 @@ impl Value {
 
 /// Output type for `File::from_str`
 pub type DidRunUpdaters = bool;
+
+/// Updater function type for `File::from_str`, where the generic `T` is for passing generic data to the updater functions
+/// 
+/// For updaters that need to edit the layout or that can fail, see `Migration` and `File::from_str_with_migrations`
+pub type UpdaterFn<T> = fn(&mut HashMap<String, Value>, &mut T);
@@ -0,0 +1,197 @@
+use crate::*;
+
+
+
+/// Differences between two versions of a file, returned by `File::diff()`
+/// 
+/// Settings are compared by value, so `added`, `removed`, and `changed` only hold real changes to the settings, and everything else (comments, empty lines, includes, and settings that moved) is listed in `layout_changes`. This can be printed as a unified diff in ECF syntax with `Display` or `to_unified_string()`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileDiff {
+	/// Version of the old file
+	pub old_version: usize,
+	/// Version of the new file
+	pub new_version: usize,
+	/// Settings that are only in the new file, sorted by key
+	pub added: Vec<(String, Value)>,
+	/// Settings that are only in the old file, sorted by key
+	pub removed: Vec<(String, Value)>,
+	/// Settings that are in both files with different values, sorted by key
+	pub changed: Vec<ChangedSetting>,
+	/// Changes to the layout that don't change any settings, in order of line
+	pub layout_changes: Vec<LayoutChange>,
+	lines: Vec<DiffLine>,
+}
+
+/// A setting that has different values in two versions of a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangedSetting {
+	/// Key of the setting
+	pub key: String,
+	/// Value in the old file
+	pub old: Value,
+	/// Value in the new file
+	pub new: Value,
+}
+
+/// A change to a file's layout that doesn't change any settings (a setting that moved is listed as removed from its old line and added at its new line)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LayoutChange {
+	/// An entry is only in the new file
+	Added {
+		/// Line of the entry in the new file
+		line: usize,
+		/// The added entry
+		entry: LayoutEntry,
+	},
+	/// An entry is only in the old file
+	Removed {
+		/// Line of the entry in the old file
+		line: usize,
+		/// The removed entry
+		entry: LayoutEntry,
+	},
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum DiffLine {
+	Same (String),
+	Removed (String),
+	Added (String),
+}
+
+impl FileDiff {
+	
+	/// Returns true if neither the settings nor the layout changed
+	pub fn is_empty(&self) -> bool {
+		self.old_version == self.new_version && self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty() && self.layout_changes.is_empty()
+	}
+	
+	/// Returns true if any setting was added, removed, or changed (or the version changed), ignoring changes that only affect the layout
+	pub fn has_setting_changes(&self) -> bool {
+		self.old_version != self.new_version || !self.added.is_empty() || !self.removed.is_empty() || !self.changed.is_empty()
+	}
+	
+	/// Formats the differences as a unified diff of the files' text, where each hunk starts with `@@ -old_line,count +new_line,count @@` and each line starts with `-` (removed), `+` (added), or ` ` (unchanged)
+	/// 
+	/// `context` is how many unchanged lines are shown around each change (`Display` uses 3). Returns an empty string if the files are written the same way
+	pub fn to_unified_string(&self, context: usize) -> String {
+		let changed_indices = self.lines.iter().enumerate().filter(|(_i, line)| !matches!(line, DiffLine::Same (_))).map(|(i, _line)| i).collect::<Vec<_>>();
+		let mut output = String::new();
+		let mut hunk_start_i = 0;
+		while hunk_start_i < changed_indices.len() {
+			let mut hunk_end_i = hunk_start_i;
+			while hunk_end_i + 1 < changed_indices.len() && changed_indices[hunk_end_i + 1] - changed_indices[hunk_end_i] <= context * 2 + 1 {
+				hunk_end_i += 1;
+			}
+			let start = changed_indices[hunk_start_i].saturating_sub(context);
+			let end = (changed_indices[hunk_end_i] + context + 1).min(self.lines.len());
+			
+			let count_lines = |range: std::ops::Range<usize>, is_old: bool| self.lines[range].iter().filter(|line| match line {
+				DiffLine::Same (_) => true,
+				DiffLine::Removed (_) => is_old,
+				DiffLine::Added (_) => !is_old,
+			}).count();
+			// like other unified diffs, an empty range starts at the line before it
+			let hunk_range = |is_old: bool| {
+				let count = count_lines(start..end, is_old);
+				let start = count_lines(0..start, is_old) + (count > 0) as usize;
+				format!("{start},{count}")
+			};
+			output += &format!("@@ -{} +{} @@\n", hunk_range(true), hunk_range(false));
+			for line in &self.lines[start..end] {
+				let (prefix, text) = match line {
+					DiffLine::Same (text) => (' ', text),
+					DiffLine::Removed (text) => ('-', text),
+					DiffLine::Added (text) => ('+', text),
+				};
+				output.push(prefix);
+				output += text;
+				output.push('\n');
+			}
+			hunk_start_i = hunk_end_i + 1;
+		}
+		output
+	}
+	
+}
+
+impl std::fmt::Display for FileDiff {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.to_unified_string(3))
+	}
+}
+
+
+
+impl File {
+	
+	/// Lists the differences between this file (the old version) and `other` (the new version)
+	/// 
+	/// Unlike comparing with `==`, this separates changes to settings from changes that only affect comments and layout, and tells exactly what changed (see `FileDiff`)
+	pub fn diff(&self, other: &File) -> FileDiff {
+		let mut added = other.values.iter().filter(|(key, _value)| !self.values.contains_key(*key)).map(|(key, value)| (key.clone(), value.clone())).collect::<Vec<_>>();
+		let mut removed = self.values.iter().filter(|(key, _value)| !other.values.contains_key(*key)).map(|(key, value)| (key.clone(), value.clone())).collect::<Vec<_>>();
+		let mut changed = self.values.iter()
+			.filter_map(|(key, old)| other.values.get(key).filter(|new| *new != old).map(|new| ChangedSetting { key: key.clone(), old: old.clone(), new: new.clone() }))
+			.collect::<Vec<_>>();
+		added.sort_by(|a, b| a.0.cmp(&b.0));
+		removed.sort_by(|a, b| a.0.cmp(&b.0));
+		changed.sort_by(|a, b| a.key.cmp(&b.key));
+		
+		// layout entries are compared without values, and entries for added or removed settings are already covered above
+		let mut layout_changes = vec!();
+		let old_line_numbers = self.layout_line_numbers();
+		let new_line_numbers = other.layout_line_numbers();
+		for op in crate::utils::diff_sequences(&self.layout, &other.layout) {
+			match op {
+				crate::utils::DiffOp::Same (_) => {}
+				crate::utils::DiffOp::Removed (i) => {
+					let entry = &self.layout[i];
+					if matches!(entry, LayoutEntry::Key (key) if !other.values.contains_key(key)) {continue;}
+					layout_changes.push(LayoutChange::Removed { line: old_line_numbers[i], entry: entry.clone() });
+				}
+				crate::utils::DiffOp::Added (i) => {
+					let entry = &other.layout[i];
+					if matches!(entry, LayoutEntry::Key (key) if !self.values.contains_key(key)) {continue;}
+					layout_changes.push(LayoutChange::Added { line: new_line_numbers[i], entry: entry.clone() });
+				}
+			}
+		}
+		
+		let old_entries = self.formatted_entries();
+		let new_entries = other.formatted_entries();
+		let mut lines = vec!();
+		for op in crate::utils::diff_sequences(&old_entries, &new_entries) {
+			match op {
+				crate::utils::DiffOp::Same (i) => lines.extend(old_entries[i].split('\n').map(|line| DiffLine::Same (line.to_string()))),
+				crate::utils::DiffOp::Removed (i) => lines.extend(old_entries[i].split('\n').map(|line| DiffLine::Removed (line.to_string()))),
+				crate::utils::DiffOp::Added (i) => lines.extend(new_entries[i].split('\n').map(|line| DiffLine::Added (line.to_string()))),
+			}
+		}
+		
+		FileDiff {
+			old_version: self.version,
+			new_version: other.version,
+			added,
+			removed,
+			changed,
+			layout_changes,
+			lines,
+		}
+	}
+	
+	/// Returns the text of each entry as `to_str()` writes it, starting with the format line (but with unlisted settings sorted by key)
+	fn formatted_entries(&self) -> Vec<String> {
+		let mut output = vec!(format!("format {}", self.version));
+		output.extend(self.layout.iter().filter_map(|entry| self.format_layout_entry(entry)));
+		let mut unlisted_keys = self.values.keys().filter(|key| self.layout_index(key).is_none()).collect::<Vec<_>>();
+		unlisted_keys.sort();
+		output.extend(unlisted_keys.into_iter().map(|key| self.format_setting(key, &self.values[key])));
+		// a final empty entry is the file's last newline, not a line of its own
+		if output.len() > 1 && output.last().is_some_and(String::is_empty) {
+			output.pop();
+		}
+		output
+	}
+	
+}
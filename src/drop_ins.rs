@@ -0,0 +1,66 @@
+use crate::*;
+use std::{io, path::{Path, PathBuf}};
+
+
+
+/// Returns the directory that holds a file's drop-in fragments, which is the file's path with `.d` added to the end (see `FileSet::load_dir()`)
+pub fn drop_in_dir(path: impl AsRef<Path>) -> PathBuf {
+	let mut output = path.as_ref().as_os_str().to_owned();
+	output.push(".d");
+	PathBuf::from(output)
+}
+
+
+
+impl FileSet {
+	
+	/// Loads a file along with its drop-in fragments, which are the `.ecf` files in the directory named after the file plus `.d` (so `app.ecf` is loaded along with `app.ecf.d/*.ecf`)
+	/// 
+	/// Fragments are applied in lexical order of their file names, and when several files define the same setting, the last one wins (so fragments override the main file, and `20-x.ecf` overrides `10-x.ecf`). Use `source_of()` to find which file set a setting, and `sources_of()` to find every file that defines it. The directory doesn't need to exist, and every fragment needs its own format line that matches the main file's format
+	/// 
+	/// The returned errors include every parse error from every file, but if the main file can't be read then only that error is returned
+	pub fn load_dir(path: impl AsRef<Path>, loader: &impl FileLoader) -> Result<(Self, Vec<SourceFileError>), SourceFileError> {
+		let path = crate::utils::normalize_path(path.as_ref());
+		let mut set = Self {
+			files: vec!(),
+			conflict_rule: KeyConflictRule::LastFileWins,
+		};
+		let mut errors = vec!();
+		if let Err(message) = set.load_fragment(&path, loader, &mut errors) {
+			return Err(SourceFileError::new(path, None, message));
+		}
+		
+		let dir = drop_in_dir(&path);
+		let mut fragment_paths = match loader.list_dir(&dir) {
+			Ok(fragment_paths) => fragment_paths,
+			Err(err) if err.kind() == io::ErrorKind::NotFound => vec!(),
+			Err(err) => {
+				errors.push(SourceFileError::new(&dir, None, format!("Could not list directory: {err}")));
+				vec!()
+			}
+		};
+		fragment_paths.retain(|fragment_path| fragment_path.extension().is_some_and(|extension| extension == "ecf"));
+		fragment_paths.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+		for fragment_path in fragment_paths {
+			let fragment_path = crate::utils::normalize_path(&fragment_path);
+			if let Err(message) = set.load_fragment(&fragment_path, loader, &mut errors) {
+				errors.push(SourceFileError::new(fragment_path, None, message));
+			}
+		}
+		Ok((set, errors))
+	}
+	
+	fn load_fragment(&mut self, path: &Path, loader: &impl FileLoader, errors: &mut Vec<SourceFileError>) -> Result<(), String> {
+		let contents = loader.read(path).map_err(|err| format!("Could not read file: {err}"))?;
+		let (file, version, parse_errors) = File::parse(contents);
+		for err in parse_errors {
+			errors.push(SourceFileError::new(path, Some(err.line), err.message));
+		}
+		if let Some(err) = self.check_version(path, version) {
+			errors.push(err);
+		}
+		self.files.push(SourceFile::new(path.to_path_buf(), file));
+		Ok(())
+	}
+	
+}
@@ -0,0 +1,111 @@
+use crate::*;
+
+
+
+/// Reads settings from environment variables, so that (with the default rules and a prefix of `APP_`) the variable `APP_WINDOW__WIDTH=800` sets `window.width` to `800`
+/// 
+/// Values are parsed with `Value::parse()`, so they use the same rules as values in ecf files (which means numbers can be negative, like `APP_OFFSET=-1`, and strings need quotes, like `APP_NAME='"example"'`). The resulting file is meant to be used as an override layer, either by adding it to a `LayeredConfig` or by passing it to `File::apply_overrides()`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EnvOverrides {
+	/// Only variables that start with this are read, and it is removed from the start of each key
+	pub prefix: String,
+	/// Text in variable names that separates namespaces, which is replaced with `.` (default: `__`)
+	pub namespace_separator: String,
+	/// What each remaining `_` in variable names is replaced with (default: `_`, but ` ` is useful for keys that contain spaces)
+	pub word_separator: String,
+	/// Whether variable names are converted to lowercase (default: true)
+	pub lowercase: bool,
+}
+
+impl EnvOverrides {
+	
+	/// Creates a reader with the given prefix (like `APP_`) and the default separator rules
+	pub fn new(prefix: impl Into<String>) -> Self {
+		Self {
+			prefix: prefix.into(),
+			namespace_separator: String::from("__"),
+			word_separator: String::from("_"),
+			lowercase: true,
+		}
+	}
+	
+	/// Sets the text that separates namespaces in variable names
+	pub fn namespace_separator(mut self, namespace_separator: impl Into<String>) -> Self {
+		self.namespace_separator = namespace_separator.into();
+		self
+	}
+	
+	/// Sets what each remaining `_` in variable names is replaced with
+	pub fn word_separator(mut self, word_separator: impl Into<String>) -> Self {
+		self.word_separator = word_separator.into();
+		self
+	}
+	
+	/// Sets whether variable names are converted to lowercase
+	pub fn lowercase(mut self, lowercase: bool) -> Self {
+		self.lowercase = lowercase;
+		self
+	}
+	
+	
+	
+	/// Returns the key that a variable sets, or `None` if the variable doesn't start with the prefix
+	pub fn key_for(&self, variable: &str) -> Option<String> {
+		let name = variable.strip_prefix(&self.prefix)?;
+		let segments = if self.namespace_separator.is_empty() {
+			vec!(name)
+		} else {
+			name.split(&*self.namespace_separator).collect::<Vec<_>>()
+		};
+		let key = segments.iter().map(|segment| segment.replace('_', &self.word_separator)).collect::<Vec<_>>().join(".");
+		Some(if self.lowercase {key.to_lowercase()} else {key})
+	}
+	
+	/// Reads every matching variable from the process's environment
+	/// 
+	/// Variables with names or values that aren't valid unicode are skipped (or reported, if the name starts with the prefix)
+	pub fn read_env(&self) -> (File, Vec<EnvOverrideError>) {
+		let mut vars = vec!();
+		let mut errors = vec!();
+		for (name, value) in std::env::vars_os() {
+			let Some(name) = name.to_str() else {continue;};
+			if !name.starts_with(&self.prefix) {continue;}
+			match value.into_string() {
+				Ok(value) => vars.push((name.to_string(), value)),
+				Err(_) => errors.push(EnvOverrideError { variable: name.to_string(), message: String::from("Value is not valid unicode") }),
+			}
+		}
+		let (file, mut parse_errors) = self.read_vars(vars);
+		errors.append(&mut parse_errors);
+		(file, errors)
+	}
+	
+	/// Same as `read_env()`, but reads the given variables instead (which is mainly useful for testing)
+	/// 
+	/// The returned file's version is 0, and its settings are ordered by variable name
+	pub fn read_vars<K: AsRef<str>, V: AsRef<str>>(&self, vars: impl IntoIterator<Item = (K, V)>) -> (File, Vec<EnvOverrideError>) {
+		let mut vars = vars.into_iter().collect::<Vec<_>>();
+		vars.sort_by(|(a, _), (b, _)| a.as_ref().cmp(b.as_ref()));
+		let mut file = File::new(0);
+		let mut errors = vec!();
+		for (variable, value) in &vars {
+			let variable = variable.as_ref();
+			let Some(key) = self.key_for(variable) else {continue;};
+			let error = |message: String| EnvOverrideError { variable: variable.to_string(), message };
+			if key.is_empty() || key.split('.').any(str::is_empty) {
+				errors.push(error(format!("Variable name does not map to a valid key (\"{key}\")")));
+				continue;
+			}
+			if file.values.contains_key(&key) {
+				errors.push(error(format!("Key \"{key}\" is already set by another variable")));
+				continue;
+			}
+			match Value::parse(value) {
+				Ok(value) => {file.set(key, value);}
+				Err(message) => errors.push(error(message)),
+			}
+		}
+		(file, errors)
+	}
+	
+}
@@ -1,3 +1,7 @@
+use crate::*;
+
+
+
 /// Errors while parsing settings
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ParseEntryError {
@@ -79,6 +83,26 @@ pub enum RetrieveSettingError {
 		/// The encountered type of the key's value
 		encountered: String,
 	},
+	/// Error for attempting to retrieve a setting value as a type that can't hold the value (like `300` as a `u8`)
+	OutOfRange {
+		/// The key that was queried
+		key: String,
+		/// The encountered value, formatted as it would be in an ecf file
+		value: String,
+		/// The type that the value was being converted to
+		target: String,
+	},
+	/// Error for attempting to retrieve a setting value as an enum when the value isn't one of the enum's spellings
+	InvalidVariant {
+		/// The key that was queried
+		key: String,
+		/// The encountered value
+		value: String,
+		/// Every spelling that would have been accepted
+		allowed: Vec<String>,
+		/// The allowed spelling that is closest to the encountered value, if it's close enough to be a likely typo
+		closest: Option<String>,
+	},
 }
 
 impl RetrieveSettingError {
@@ -91,6 +115,12 @@ impl RetrieveSettingError {
 	pub(crate) fn new_wrong_multiple_type(key: impl Into<String>, expected: Vec<String>, encountered: impl Into<String>) -> Self {
 		Self::WrongMultipleType { key: key.into(), expected, encountered: encountered.into() }
 	}
+	pub(crate) fn new_out_of_range(key: impl Into<String>, value: impl Into<String>, target: impl Into<String>) -> Self {
+		Self::OutOfRange { key: key.into(), value: value.into(), target: target.into() }
+	}
+	pub(crate) fn new_invalid_variant(key: impl Into<String>, value: impl Into<String>, allowed: &[&str], closest: Option<&str>) -> Self {
+		Self::InvalidVariant { key: key.into(), value: value.into(), allowed: allowed.iter().map(|v| v.to_string()).collect(), closest: closest.map(str::to_string) }
+	}
 }
 
 impl std::error::Error for RetrieveSettingError {}
@@ -116,6 +146,339 @@ impl std::fmt::Display for RetrieveSettingError {
 				write!(f, " but found type '{encountered}'")?;
 				Ok(())
 			}
+			Self::OutOfRange { key, value, target } => write!(f, "Setting '{key}' has the value {value}, which cannot be represented as type '{target}'"),
+			Self::InvalidVariant { key, value, allowed, closest } => {
+				write!(f, "Setting '{key}' has the value \"{value}\", but the allowed values are ")?;
+				for (i, allowed_value) in allowed.iter().enumerate() {
+					if i > 0 {write!(f, ", ")?;}
+					write!(f, "\"{allowed_value}\"")?;
+				}
+				if let Some(closest) = closest {
+					write!(f, " (did you mean \"{closest}\"?)")?;
+				}
+				Ok(())
+			}
+		}
+	}
+}
+
+
+
+/// Error when retrieving a setting, along with where the setting is defined
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SettingError {
+	/// Line number of the setting (using 1-based indexing), or `None` if the setting isn't in the file's layout
+	pub line: Option<usize>,
+	/// The underlying error
+	pub error: RetrieveSettingError,
+}
+
+impl std::error::Error for SettingError {}
+
+impl std::fmt::Display for SettingError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.line {
+			Some(line) => write!(f, "Invalid setting at line {line}: {}", self.error),
+			None => write!(f, "Invalid setting: {}", self.error),
+		}
+	}
+}
+
+
+
+/// Problem found by `Schema::validate()`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaViolation {
+	/// Key of the invalid setting
+	pub key: String,
+	/// Line number of the setting (using 1-based indexing), or `None` if the setting isn't in the file's layout
+	pub line: Option<usize>,
+	/// Value of the setting, or `None` if the setting is missing
+	pub value: Option<Value>,
+	/// What is wrong with the setting
+	pub kind: ViolationKind,
+}
+
+/// Describes what is wrong with a setting according to a schema
+#[derive(Debug, Clone, PartialEq)]
+pub enum ViolationKind {
+	/// A required setting is missing
+	Missing,
+	/// The setting's value is the wrong type
+	WrongType {
+		/// The type defined in the schema
+		expected: ValueType,
+		/// The encountered type, according to `Value::type_as_string()`
+		encountered: &'static str,
+	},
+	/// The setting's value is smaller than the schema's minimum
+	BelowMinimum {
+		/// The smallest allowed value
+		min: f64,
+	},
+	/// The setting's value is larger than the schema's maximum
+	AboveMaximum {
+		/// The largest allowed value
+		max: f64,
+	},
+	/// The setting's value doesn't match the schema's pattern
+	PatternMismatch {
+		/// The pattern that the value should match
+		pattern: String,
+	},
+	/// The setting's value isn't in the schema's list of allowed values
+	NotAllowed {
+		/// Every allowed value
+		allowed: Vec<Value>,
+	},
+}
+
+impl std::error::Error for SchemaViolation {}
+
+impl std::fmt::Display for SchemaViolation {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.line {
+			Some(line) => write!(f, "Invalid setting '{}' at line {line}: ", self.key)?,
+			None => write!(f, "Invalid setting '{}': ", self.key)?,
+		}
+		match &self.kind {
+			ViolationKind::Missing => write!(f, "this setting is required but does not exist"),
+			ViolationKind::WrongType { expected, encountered } => write!(f, "expected type '{}', but found type '{encountered}'", expected.as_str()),
+			ViolationKind::BelowMinimum { min } => write!(f, "value cannot be less than {min}"),
+			ViolationKind::AboveMaximum { max } => write!(f, "value cannot be greater than {max}"),
+			ViolationKind::PatternMismatch { pattern } => write!(f, "value does not match the pattern \"{pattern}\""),
+			ViolationKind::NotAllowed { allowed } => {
+				write!(f, "value must be one of ")?;
+				for (i, allowed_value) in allowed.iter().enumerate() {
+					if i > 0 {write!(f, ", ")?;}
+					write!(f, "{}", allowed_value.format())?;
+				}
+				Ok(())
+			}
+		}
+	}
+}
+
+
+
+/// Errors while reading a schema from an ecf file
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SchemaParseError {
+	/// Line number of invalid attribute (using 1-based indexing), or `None` if the attribute isn't in the file's layout
+	pub line: Option<usize>,
+	/// Error message / reason for being invalid
+	pub message: String,
+}
+
+impl std::error::Error for SchemaParseError {}
+
+impl std::fmt::Display for SchemaParseError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.line {
+			Some(line) => write!(f, "Invalid schema attribute at line {line}: {}", self.message),
+			None => write!(f, "Invalid schema attribute: {}", self.message),
+		}
+	}
+}
+
+
+
+/// Errors while migrating a file to a different version
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MigrationError {
+	/// Version that the failed step was migrating from
+	pub from_version: usize,
+	/// Version that the failed step was migrating to
+	pub to_version: usize,
+	/// Error message / reason for failing
+	pub message: String,
+}
+
+impl MigrationError {
+	/// Creates a new migration error (inside a migration, `MigrationContext::error()` is usually easier)
+	pub fn new(from_version: usize, to_version: usize, message: impl Into<String>) -> Self {
+		Self {
+			from_version,
+			to_version,
+			message: message.into(),
+		}
+	}
+}
+
+impl std::error::Error for MigrationError {}
+
+impl std::fmt::Display for MigrationError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Failed to migrate settings from format {} to format {}: {}", self.from_version, self.to_version, self.message)
+	}
+}
+
+
+
+/// Error for using a layer name that isn't in a `LayeredConfig`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UnknownLayerError {
+	/// Name of the missing layer
+	pub layer: String,
+}
+
+impl std::error::Error for UnknownLayerError {}
+
+impl std::fmt::Display for UnknownLayerError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "There is no configuration layer named \"{}\"", self.layer)
+	}
+}
+
+
+
+/// Errors while reading settings from environment variables
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EnvOverrideError {
+	/// Name of the invalid environment variable
+	pub variable: String,
+	/// Error message / reason for being invalid
+	pub message: String,
+}
+
+impl std::error::Error for EnvOverrideError {}
+
+impl std::fmt::Display for EnvOverrideError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Invalid environment variable {}: {}", self.variable, self.message)
+	}
+}
+
+
+
+/// Errors while reading settings from command-line arguments
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CliArgError {
+	/// The invalid argument
+	pub argument: String,
+	/// Error message / reason for being invalid
+	pub message: String,
+}
+
+impl CliArgError {
+	pub(crate) fn new(argument: impl Into<String>, message: impl Into<String>) -> Self {
+		Self {
+			argument: argument.into(),
+			message: message.into(),
+		}
+	}
+}
+
+impl std::error::Error for CliArgError {}
+
+impl std::fmt::Display for CliArgError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Invalid argument \"{}\": {}", self.argument, self.message)
+	}
+}
+
+
+
+/// Errors while loading or saving settings that are spread across several files
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SourceFileError {
+	/// Path of the file that caused the error
+	pub path: std::path::PathBuf,
+	/// Line number of the error within the file (using 1-based indexing), if the error is tied to a line
+	pub line: Option<usize>,
+	/// Error message / reason for failing
+	pub message: String,
+}
+
+impl SourceFileError {
+	pub(crate) fn new(path: impl Into<std::path::PathBuf>, line: Option<usize>, message: impl Into<String>) -> Self {
+		Self {
+			path: path.into(),
+			line,
+			message: message.into(),
+		}
+	}
+}
+
+impl std::error::Error for SourceFileError {}
+
+impl std::fmt::Display for SourceFileError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.line {
+			Some(line) => write!(f, "Error in \"{}\" at line {line}: {}", self.path.display(), self.message),
+			None => write!(f, "Error in \"{}\": {}", self.path.display(), self.message),
+		}
+	}
+}
+
+
+
+/// Errors while expanding references in a setting (see `File::get_interpolated()`)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InterpolationError {
+	/// Key of the setting that couldn't be expanded
+	pub key: String,
+	/// Line number of the setting (using 1-based indexing), or `None` if the setting isn't in the file's layout
+	pub line: Option<usize>,
+	/// Error message / reason for failing
+	pub message: String,
+}
+
+impl std::error::Error for InterpolationError {}
+
+impl std::fmt::Display for InterpolationError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.line {
+			Some(line) => write!(f, "Could not expand setting '{}' at line {line}: {}", self.key, self.message),
+			None => write!(f, "Could not expand setting '{}': {}", self.key, self.message),
+		}
+	}
+}
+
+
+
+/// Errors while loading or saving a file on disk
+#[derive(Debug)]
+pub enum FileError {
+	/// The file couldn't be read or written
+	Io {
+		/// Path of the file
+		path: std::path::PathBuf,
+		/// The underlying error
+		error: std::io::Error,
+	},
+	/// An entry in the file couldn't be parsed (this doesn't stop the rest of the file from loading)
+	Parse {
+		/// Path of the file
+		path: std::path::PathBuf,
+		/// The underlying error
+		error: ParseEntryError,
+	},
+}
+
+impl FileError {
+	/// Returns the path of the file that caused the error
+	pub fn path(&self) -> &std::path::Path {
+		match self {
+			Self::Io { path, .. } | Self::Parse { path, .. } => path,
+		}
+	}
+}
+
+impl std::error::Error for FileError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::Io { error, .. } => Some(error),
+			Self::Parse { error, .. } => Some(error),
+		}
+	}
+}
+
+impl std::fmt::Display for FileError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Io { path, error } => write!(f, "Could not access \"{}\": {error}", path.display()),
+			Self::Parse { path, error } => write!(f, "Error in \"{}\": {error}", path.display()),
 		}
 	}
 }
@@ -1,12 +1,16 @@
 use crate::*;
-use std::{collections::{HashMap, HashSet}, ops::{Deref, DerefMut}};
+use std::{borrow::Cow, collections::{HashMap, HashSet}, ops::{Deref, DerefMut}};
 
 
 
 
 
 /// Holds data for a file's contents, layout, and version
-#[derive(Debug, Clone, PartialEq)]
+/// 
+/// Files are created with `new()` or one of the loading functions, since some of the fields are private (so unlike previous versions, a `File` can't be built with a struct literal)
+/// 
+/// Comparing files only compares their contents, layout, and version, so files that were parsed from the same text are always equal (even if they have different aliases, coercion settings, or coercion warnings)
+#[derive(Debug, Clone)]
 pub struct File {
 	/// Contents of file
 	pub values: HashMap<String, Value>,
@@ -14,6 +18,18 @@ pub struct File {
 	pub layout: Vec<LayoutEntry>,
 	/// Version of file (strongly recommended to hold the latest version of settings that your application supports)
 	pub version: usize,
+	/// Aliases used by the getters to resolve old keys to new keys (these are added by `apply_aliases()`)
+	pub aliases: Vec<KeyAlias>,
+	/// Coercion settings used by the getters (strict by default)
+	pub coercion: Coercion,
+	pub(crate) coercion_log: CoercionLog,
+	pub(crate) origin: Option<FileOrigin>,
+}
+
+impl PartialEq for File {
+	fn eq(&self, other: &Self) -> bool {
+		self.values == other.values && self.layout == other.layout && self.version == other.version
+	}
 }
 
 impl Deref for File {
@@ -35,47 +51,76 @@ impl File {
 	
 	
 	
+	/// Creates an empty file with the given version
+	pub fn new(version: usize) -> Self {
+		Self {
+			values: HashMap::new(),
+			layout: vec!(),
+			version,
+			aliases: vec!(),
+			coercion: Coercion::STRICT,
+			coercion_log: CoercionLog::default(),
+			origin: None,
+		}
+	}
+	
+	
+	
 	/// Converts a settings file into a layout + values, opposite of `format_settings()`
 	/// 
 	/// The generic `T` is for passing generic data to the updater functions
-	pub fn from_str<T>(contents: impl AsRef<str>, updater_fns: &[fn(&mut HashMap<String, Value>, &mut T)], args: &mut T) -> (Self, DidRunUpdaters, Vec<ParseEntryError>) {
-		let mut layout = vec!();
-		let mut values = HashMap::new();
+	pub fn from_str<T>(contents: impl AsRef<str>, updater_fns: &[UpdaterFn<T>], args: &mut T) -> (Self, DidRunUpdaters, Vec<ParseEntryError>) {
+		let (mut file, version, mut errors) = Self::parse(contents);
+		
+		let latest_version = updater_fns.len() + 1;
+		let did_run_updaters = match version {
+			Some(version) if version > latest_version => {
+				errors.push(ParseEntryError::new(0, format!("Format {version} is newer than the latest supported format ({latest_version}), so no updaters were run")));
+				file.version = version;
+				return (file, false, errors);
+			}
+			Some(0) => {
+				errors.push(ParseEntryError::new(0, "Format 0 is not a valid version, assuming version is latest"));
+				false
+			}
+			Some(version) => {
+				let fns_to_run = &updater_fns[version - 1 ..];
+				for updater_fn in fns_to_run {
+					(updater_fn)(&mut file.values, args);
+				}
+				!fns_to_run.is_empty()
+			}
+			None => {
+				errors.push(ParseEntryError::new(0, "Could not find version, assuming version is latest"));
+				false
+			}
+		};
+		
+		file.version = latest_version;
+		(file, did_run_updaters, errors)
+	}
+	
+	/// Parses a settings file without running any updaters, and returns the version that was found in the file
+	/// 
+	/// The returned file's `version` is the file's version, or 0 if it couldn't be found
+	pub(crate) fn parse(contents: impl AsRef<str>) -> (Self, Option<usize>, Vec<ParseEntryError>) {
+		let mut file = Self::new(0);
 		let mut errors = vec!();
 		
 		let lines = contents.as_ref().split('\n').collect::<Vec<_>>();
 		let version = get_file_version(lines[0].trim());
 		let mut line_i = 1;
-		loop {
-			let result = parse_line(&lines, &mut line_i, &mut layout, &mut values);
+		while line_i < lines.len() {
+			let result = parse_line(&lines, &mut line_i, &mut file.layout, &mut file.values);
 			if let Err(err) = result {
-				layout.push(LayoutEntry::Comment (lines[line_i].to_string()));
+				file.layout.push(LayoutEntry::Comment (lines[line_i].to_string()));
 				errors.push(err);
 			}
 			line_i += 1;
-			if line_i >= lines.len() {break;}
 		}
 		
-		let did_run_updaters = if let Some(version) = version {
-			let fns_to_run = &updater_fns[version - 1 ..];
-			for updater_fn in fns_to_run {
-				(updater_fn)(&mut values, args);
-			}
-			!fns_to_run.is_empty()
-		} else {
-			errors.push(ParseEntryError::new(0, "Could not find version, assuming version is latest"));
-			false
-		};
-		
-		(
-			Self {
-				values,
-				layout,
-				version: updater_fns.len() + 1,
-			},
-			did_run_updaters,
-			errors,
-		)
+		file.version = version.unwrap_or(0);
+		(file, version, errors)
 	}
 	
 	
@@ -87,50 +132,103 @@ impl File {
 		let mut errors = vec!();
 		let mut printed_keys = HashSet::new();
 		for entry in &self.layout {
-			match entry {
-				LayoutEntry::Empty => {}
-				LayoutEntry::Comment (comment) => {
-					if comment.contains('\n') {
-						output += "##\n";
-						output += comment;
-						output += "\n##";
-					} else {
-						output.push('#');
-						output += comment;
-					}
-				}
-				LayoutEntry::Key (key) => {
-					output += key;
-					output += ": ";
-					let value = self.get(key);
-					if let Some(value) = value {
-						output += &value.format();
-					} else {
-						errors.push(FormatEntryError::new(key));
-						continue;
-					};
-					printed_keys.insert(key.to_string());
+			let Some(formatted) = self.format_layout_entry(entry) else {
+				if let LayoutEntry::Key (key) = entry {
+					errors.push(FormatEntryError::new(key));
 				}
+				continue;
+			};
+			output += &formatted;
+			if let LayoutEntry::Key (key) = entry {
+				printed_keys.insert(key.to_string());
 			}
 			output.push('\n');
 		}
 		for (key, value) in &self.values {
 			if printed_keys.contains(key) {continue;}
-			output += key;
-			output += ": ";
-			output += &value.format();
+			output += &self.format_setting(key, value);
 			output.push('\n');
 		}
 		output.pop();
 		(output, errors)
 	}
 	
+	/// Formats a single layout entry the same way that `to_str()` writes it (without the final newline), or returns `None` for a key that doesn't have a value
+	pub(crate) fn format_layout_entry(&self, entry: &LayoutEntry) -> Option<String> {
+		match entry {
+			LayoutEntry::Empty => Some(String::new()),
+			LayoutEntry::Comment (comment) if comment.contains('\n') => Some(format!("##\n{comment}\n##")),
+			LayoutEntry::Comment (comment) => Some(format!("#{comment}")),
+			LayoutEntry::Include (path) => Some(format!("include \"{path}\"")),
+			LayoutEntry::Key (key) => self.values.get(key).map(|value| self.format_setting(key, value)),
+		}
+	}
 	
+	pub(crate) fn format_setting(&self, key: &str, value: &Value) -> String {
+		format!("{key}: {}", self.value_for_saving(key, value).format().trim_end_matches('\n'))
+	}
+	
+	fn value_for_saving<'a>(&self, key: &str, value: &'a Value) -> Cow<'a, Value> {
+		if !self.coercion.rewrite_on_save {return Cow::Borrowed(value);}
+		let coerced =
+			self.coercion_log.get_all().into_iter()
+			.find(|warning| warning.key == key && warning.original == *value)
+			.map(|warning| warning.coerced);
+		match coerced {
+			Some(coerced) => Cow::Owned(coerced),
+			None => Cow::Borrowed(value),
+		}
+	}
+	
+	
+	
+	/// Gets the value of a setting as any type that implements `FromValue` (or returns an error if the setting is missing, if it's holding the wrong type, or if it doesn't fit in `T`)
+	/// 
+	/// This uses the coercion settings in `self.coercion`
+	/// 
+	/// Note: this shadows `HashMap::get()`, so use `file.values.get()` to get the raw `Value`
+	pub fn get<T: FromValue>(&self, key: impl AsRef<str>) -> Result<T, RetrieveSettingError> {
+		self.get_coerced(key, self.coercion)
+	}
+	
+	/// Same as `get()`, but uses the given coercion settings instead of `self.coercion`
+	/// 
+	/// Coercion only applies to single values, so the elements of types like `Vec<T>` are never coerced
+	pub fn get_coerced<T: FromValue>(&self, key: impl AsRef<str>, coercion: Coercion) -> Result<T, RetrieveSettingError> {
+		let key = self.resolve_key(key.as_ref());
+		let err = match T::from_file(self, key) {
+			Ok(output) => return Ok(output),
+			Err(err) => err,
+		};
+		let expected = match &err {
+			RetrieveSettingError::WrongSingularType { key: err_key, expected, .. } if err_key == key => vec!(expected.clone()),
+			RetrieveSettingError::WrongMultipleType { key: err_key, expected, .. } if err_key == key => expected.clone(),
+			_ => return Err(err),
+		};
+		let Some(value) = self.values.get(key) else {return Err(err);};
+		for expected_type in expected {
+			let Some(coerced) = coercion.coerce(value, &expected_type) else {continue;};
+			let output = T::from_value(key, &coerced)?;
+			self.coercion_log.record(CoercionWarning { key: key.to_string(), original: value.clone(), coerced });
+			return Ok(output);
+		}
+		Err(err)
+	}
+	
+	/// Returns every coercion that has happened while retrieving values
+	pub fn coercion_warnings(&self) -> Vec<CoercionWarning> {
+		self.coercion_log.get_all()
+	}
+	
+	/// Clears the list of coercions (note: this also means `coercion.rewrite_on_save` won't rewrite any of the previously coerced values)
+	pub fn clear_coercion_warnings(&self) {
+		self.coercion_log.clear();
+	}
 	
 	/// Basically an assert for a setting key existing but being left empty
 	pub fn get_empty(&self, key: impl AsRef<str>) -> Result<(), RetrieveSettingError> {
-		let key = key.as_ref();
-		match self.get(key) {
+		let key = self.resolve_key(key.as_ref());
+		match self.values.get(key) {
 			None => Err(RetrieveSettingError::new_missing(key)),
 			Some(Value::Empty) => Ok(()),
 			Some(value) => Err(RetrieveSettingError::new_wrong_singular_type(key.to_string(), "empty", value.type_as_string())),
@@ -139,18 +237,13 @@ impl File {
 	
 	/// Gets the value of a setting as an int (or returns an error if the setting is missing or if it's holding the wrong type)
 	pub fn get_int(&self, key: impl AsRef<str>) -> Result<i64, RetrieveSettingError> {
-		let key = key.as_ref();
-		match self.get(key) {
-			None => Err(RetrieveSettingError::new_missing(key)),
-			Some(Value::I64 (v)) => Ok(*v),
-			Some(value) => Err(RetrieveSettingError::new_wrong_singular_type(key.to_string(), "Int", value.type_as_string())),
-		}
+		self.get::<i64>(key)
 	}
 	
-	/// Gets the value of a setting as an int as mut (or returns an error if the setting is missing or if it's holding the wrong type)
+	/// Gets the value of a setting as an int as mut, without any coercion (or returns an error if the setting is missing or if it's holding the wrong type)
 	pub fn get_int_mut(&mut self, key: impl AsRef<str>) -> Result<&mut i64, RetrieveSettingError> {
-		let key = key.as_ref();
-		match self.get_mut(key) {
+		let key = self.resolve_key(key.as_ref()).to_string();
+		match self.values.get_mut(&key) {
 			None => Err(RetrieveSettingError::new_missing(key)),
 			Some(Value::I64 (v)) => Ok(v),
 			Some(value) => Err(RetrieveSettingError::new_wrong_singular_type(key.to_string(), "Int", value.type_as_string())),
@@ -159,18 +252,13 @@ impl File {
 	
 	/// Gets the value of a setting as a float (or returns an error if the setting is missing or if it's holding the wrong type)
 	pub fn get_float(&self, key: impl AsRef<str>) -> Result<f64, RetrieveSettingError> {
-		let key = key.as_ref();
-		match self.get(key) {
-			None => Err(RetrieveSettingError::new_missing(key)),
-			Some(Value::F64 (v)) => Ok(*v),
-			Some(value) => Err(RetrieveSettingError::new_wrong_singular_type(key.to_string(), "Float", value.type_as_string())),
-		}
+		self.get::<f64>(key)
 	}
 	
-	/// Gets the value of a setting as a float as mut (or returns an error if the setting is missing or if it's holding the wrong type)
+	/// Gets the value of a setting as a float as mut, without any coercion (or returns an error if the setting is missing or if it's holding the wrong type)
 	pub fn get_float_mut(&mut self, key: impl AsRef<str>) -> Result<&mut f64, RetrieveSettingError> {
-		let key = key.as_ref();
-		match self.get_mut(key) {
+		let key = self.resolve_key(key.as_ref()).to_string();
+		match self.values.get_mut(&key) {
 			None => Err(RetrieveSettingError::new_missing(key)),
 			Some(Value::F64 (v)) => Ok(v),
 			Some(value) => Err(RetrieveSettingError::new_wrong_singular_type(key.to_string(), "Float", value.type_as_string())),
@@ -181,29 +269,30 @@ impl File {
 	/// 
 	/// This does'n't' have a `get_number_mut()` because there's no return type that would make sense
 	pub fn get_number(&self, key: impl AsRef<str>) -> Result<f64, RetrieveSettingError> {
-		let key = key.as_ref();
-		match self.get(key) {
+		let key = self.resolve_key(key.as_ref());
+		match self.values.get(key) {
 			None => Err(RetrieveSettingError::new_missing(key)),
 			Some(Value::I64 (v)) => Ok(*v as f64),
 			Some(Value::F64 (v)) => Ok(*v),
-			Some(value) => Err(RetrieveSettingError::new_wrong_multiple_type(key.to_string(), vec!(String::from("Int"), String::from("Float")), value.type_as_string())),
+			Some(value) => {
+				if let Some(coerced @ Value::F64 (v)) = self.coercion.coerce(value, "Float") {
+					self.coercion_log.record(CoercionWarning { key: key.to_string(), original: value.clone(), coerced });
+					return Ok(v);
+				}
+				Err(RetrieveSettingError::new_wrong_multiple_type(key.to_string(), vec!(String::from("Int"), String::from("Float")), value.type_as_string()))
+			}
 		}
 	}
 	
 	/// Gets the value of a setting as a bool (or returns an error if the setting is missing or if it's holding the wrong type)
 	pub fn get_bool(&self, key: impl AsRef<str>) -> Result<bool, RetrieveSettingError> {
-		let key = key.as_ref();
-		match self.get(key) {
-			None => Err(RetrieveSettingError::new_missing(key)),
-			Some(Value::Bool (v)) => Ok(*v),
-			Some(value) => Err(RetrieveSettingError::new_wrong_singular_type(key.to_string(), "Bool", value.type_as_string())),
-		}
+		self.get::<bool>(key)
 	}
 	
-	/// Gets the value of a setting as a bool as mut (or returns an error if the setting is missing or if it's holding the wrong type)
+	/// Gets the value of a setting as a bool as mut, without any coercion (or returns an error if the setting is missing or if it's holding the wrong type)
 	pub fn get_bool_mut(&mut self, key: impl AsRef<str>) -> Result<&mut bool, RetrieveSettingError> {
-		let key = key.as_ref();
-		match self.get_mut(key) {
+		let key = self.resolve_key(key.as_ref()).to_string();
+		match self.values.get_mut(&key) {
 			None => Err(RetrieveSettingError::new_missing(key)),
 			Some(Value::Bool (v)) => Ok(v),
 			Some(value) => Err(RetrieveSettingError::new_wrong_singular_type(key.to_string(), "Bool", value.type_as_string())),
@@ -212,8 +301,8 @@ impl File {
 	
 	/// Gets the value of a setting as a string (or returns an error if the setting is missing or if it's holding the wrong type)
 	pub fn get_str(&self, key: impl AsRef<str>) -> Result<&str, RetrieveSettingError> {
-		let key = key.as_ref();
-		match self.get(key) {
+		let key = self.resolve_key(key.as_ref());
+		match self.values.get(key) {
 			None => Err(RetrieveSettingError::new_missing(key)),
 			Some(Value::String (v)) => Ok(v),
 			Some(value) => Err(RetrieveSettingError::new_wrong_singular_type(key.to_string(), "String", value.type_as_string())),
@@ -222,8 +311,8 @@ impl File {
 	
 	/// Gets the value of a setting as a string as mut (or returns an error if the setting is missing or if it's holding the wrong type)
 	pub fn get_string_mut(&mut self, key: impl AsRef<str>) -> Result<&mut String, RetrieveSettingError> {
-		let key = key.as_ref();
-		match self.get_mut(key) {
+		let key = self.resolve_key(key.as_ref()).to_string();
+		match self.values.get_mut(&key) {
 			None => Err(RetrieveSettingError::new_missing(key)),
 			Some(Value::String (v)) => Ok(v),
 			Some(value) => Err(RetrieveSettingError::new_wrong_singular_type(key.to_string(), "String", value.type_as_string())),
@@ -232,7 +321,81 @@ impl File {
 	
 	
 	
+	/// Gets the value of a setting as an enum (or returns an error if the setting is missing, if it isn't a string, or if it isn't one of the enum's spellings)
+	pub fn get_enum<T: EcfEnum>(&self, key: impl AsRef<str>) -> Result<T, RetrieveSettingError> {
+		let key = self.resolve_key(key.as_ref());
+		match self.values.get(key) {
+			None => Err(RetrieveSettingError::new_missing(key)),
+			Some(value) => T::from_setting(key, value),
+		}
+	}
+	
+	
+	
+	/// Sets the value of a setting and returns the previous value, adding the key to the end of the layout if it isn't already in the layout
+	pub fn set(&mut self, key: impl AsRef<str>, value: impl IntoValue) -> Option<Value> {
+		let key = self.resolve_key(key.as_ref()).to_string();
+		if self.layout_index(&key).is_none() {
+			self.layout.push(LayoutEntry::Key (key.clone()));
+		}
+		self.values.insert(key, value.into_value())
+	}
+	
+	/// Sets every setting from another file using `set()`, which is how an override layer (like one from `EnvOverrides`) can be applied directly to a file
+	pub fn apply_overrides(&mut self, overrides: &File) {
+		let mut keys = overrides.layout.iter().filter_map(|entry| match entry {
+			LayoutEntry::Key (key) if overrides.values.contains_key(key) => Some(key),
+			_ => None,
+		}).collect::<Vec<_>>();
+		let mut unlisted_keys = overrides.values.keys().filter(|key| overrides.layout_index(key).is_none()).collect::<Vec<_>>();
+		unlisted_keys.sort();
+		keys.extend(unlisted_keys);
+		for key in keys {
+			self.set(key, overrides.values[key].clone());
+		}
+	}
+	
+	
+	
+	/// Returns the line number (using 1-based indexing) that a key is on according to the layout, or `None` if the key isn't in the layout
+	/// 
+	/// This is the same line as in the parsed file, as long as the layout hasn't been changed since then
+	pub fn line_of(&self, key: impl AsRef<str>) -> Option<usize> {
+		let key = self.resolve_key(key.as_ref());
+		let index = self.layout_index(key)?;
+		Some(self.layout_line_numbers()[index])
+	}
+	
+	/// Returns the index of a key's entry in the layout
+	pub fn layout_index(&self, key: impl AsRef<str>) -> Option<usize> {
+		let key = key.as_ref();
+		self.layout.iter().position(|entry| matches!(entry, LayoutEntry::Key (entry_key) if entry_key == key))
+	}
+	
+	/// Returns the line number (using 1-based indexing) of each entry in the layout
+	pub fn layout_line_numbers(&self) -> Vec<usize> {
+		let mut output = Vec::with_capacity(self.layout.len());
+		let mut line = 2; // line 1 is the format specifier
+		for entry in &self.layout {
+			output.push(line);
+			line += match entry {
+				LayoutEntry::Empty => 1,
+				LayoutEntry::Comment (comment) if comment.contains('\n') => comment.split('\n').count() + 2,
+				LayoutEntry::Comment (_) => 1,
+				LayoutEntry::Include (_) => 1,
+				LayoutEntry::Key (key) => match self.values.get(key) {
+					Some(Value::String (string)) if string.contains('\n') => string.split('\n').count() + 1,
+					_ => 1,
+				},
+			};
+		}
+		output
+	}
+	
+	
+	
 	/// Add key-value pairs to the `values` hashmap for keys that aren't set
+	#[deprecated(note = "use `Schema::apply_defaults()` instead, which also adds the settings' descriptions as comments")]
 	pub fn add_missing_values(&mut self, defaults: impl IntoIterator<Item = (&str, Value)>) {
 		for (key, value) in defaults {
 			if self.contains_key(key) {continue;}
@@ -299,14 +462,14 @@ fn parse_multiline_comment(
 	let start_line_i = *line_i;
 	let mut output = String::new();
 	*line_i += 1;
-	while lines[*line_i].trim() != "##" {
+	while *line_i < lines.len() && lines[*line_i].trim() != "##" {
 		output += lines[*line_i];
 		output.push('\n');
 		*line_i += 1;
-		if *line_i == lines.len() {
-			*line_i = start_line_i;
-			return Err(ParseEntryError::new(start_line_i, "Could not find an end of this multiline comment. To end a multiline comment, its last line should be nothing but '##'."));
-		}
+	}
+	if *line_i == lines.len() {
+		*line_i = start_line_i;
+		return Err(ParseEntryError::new(start_line_i, "Could not find an end of this multiline comment. To end a multiline comment, its last line should be nothing but '##'."));
 	}
 	output.pop();
 	Ok(LayoutEntry::Comment (output))
@@ -314,6 +477,20 @@ fn parse_multiline_comment(
 
 
 
+/// Parses a value on its own (without a key), used for `Value::parse()`
+pub(crate) fn parse_value_text(text: &str) -> Result<Value, String> {
+	let line = format!("value: {}", text.trim());
+	let lines = line.split('\n').collect::<Vec<_>>();
+	let mut line_i = 0;
+	let value = parse_value(&lines, &mut line_i, 5).map_err(|err| err.message)?;
+	if line_i + 1 < lines.len() {
+		return Err(String::from("Invalid value, found extra text after the value"));
+	}
+	Ok(value)
+}
+
+
+
 fn parse_value(lines: &[&str], line_i: &mut usize, colon_index: usize) -> Result<Value, ParseEntryError> {
 	let line_trimmed = lines[*line_i].trim();
 	
@@ -332,7 +509,8 @@ fn parse_value(lines: &[&str], line_i: &mut usize, colon_index: usize) -> Result
 		_ => {}
 	}
 	let first_char = value.chars().next().unwrap(); // safety: value cannot be empty because it has to have non-whitespace char(s)
-	if first_char.is_ascii_digit() {
+	let is_number = first_char.is_ascii_digit() || (first_char == '-' && value[1..].starts_with(|c: char| c.is_ascii_digit()));
+	if is_number {
 		if let Ok(i64_value) = value.parse::<i64>() {return Ok(Value::I64 (i64_value));}
 		if let Ok(f64_value) = value.parse::<f64>() {return Ok(Value::F64 (f64_value));}
 	}
@@ -350,6 +528,9 @@ fn parse_value(lines: &[&str], line_i: &mut usize, colon_index: usize) -> Result
 fn parse_multiline_string(lines: &[&str], line_i: &mut usize) -> Result<Value, ParseEntryError> {
 	let mut output = String::new();
 	let start_i = *line_i;
+	if start_i + 1 == lines.len() {
+		return Err(ParseEntryError::new(start_i, String::from("Invalid value, multiline strings cannot be empty")));
+	}
 	*line_i += 1;
 	let mut curr_line = lines[*line_i].trim_start();
 	while curr_line.starts_with('"') {
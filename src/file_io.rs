@@ -0,0 +1,234 @@
+use crate::*;
+use std::{ffi::OsString, fs, hash::{DefaultHasher, Hash, Hasher}, io::{self, Write}, path::{Path, PathBuf}, sync::atomic::{AtomicUsize, Ordering}};
+
+
+
+/// Which backup `File::save_with_options()` keeps, where backups are stored next to the file with `.bak` added to the end (see `backup_path()`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Backup {
+	/// Don't keep a backup
+	#[default]
+	None,
+	/// Copy the file that is about to be replaced (if it exists)
+	PreviousVersion,
+	/// Only keep a backup if updaters ran while loading, in which case the file from before it was updated is copied (this only happens on the first save after loading, so later saves don't replace the backup)
+	PreMigration,
+}
+
+/// Options for `File::save_with_options()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SaveOptions {
+	/// Which backup to keep, if any (default: `Backup::None`)
+	pub backup: Backup,
+	/// Whether changes made to the file on disk since it was loaded or last saved are merged in before saving, instead of being overwritten (default: true)
+	pub merge_changes: bool,
+}
+
+impl Default for SaveOptions {
+	fn default() -> Self {
+		Self {
+			backup: Backup::None,
+			merge_changes: true,
+		}
+	}
+}
+
+/// What happened while saving a file with `File::save()` or `File::save_with_options()`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SaveReport {
+	/// Errors from formatting the file (see `File::to_str()`)
+	pub format_errors: Vec<FormatEntryError>,
+	/// Whether the file was changed on disk since it was loaded or last saved, in which case those changes were merged into this file before saving
+	pub merged_changes: bool,
+	/// Settings that were changed both on disk and in this file (as `theirs` and `ours`), where the value in this file was saved and the value from disk is only kept here
+	pub conflicts: Vec<MergeConflict>,
+}
+
+/// Output type for `File::load_with_aliases()`: the file, whether updaters ran, parse errors, and a warning for every old key that was renamed
+pub type AliasedLoad = (File, DidRunUpdaters, Vec<FileError>, Vec<AliasWarning>);
+
+/// Returns the path that backups of a file are saved to, which is the file's path with `.bak` added to the end
+pub fn backup_path(path: impl AsRef<Path>) -> PathBuf {
+	let mut output = path.as_ref().as_os_str().to_owned();
+	output.push(".bak");
+	PathBuf::from(output)
+}
+
+
+
+/// Remembers where a file's contents came from, so saving can make backups and merge changes made on disk
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FileOrigin {
+	/// Hash of the text that was last loaded or saved
+	pub(crate) disk_hash: u64,
+	/// Text that was last loaded or saved, which is the common ancestor when merging changes made on disk
+	pub(crate) disk_text: String,
+	/// Text of the file from before updaters ran, until it has been backed up
+	pub(crate) pre_migration_text: Option<String>,
+}
+
+impl FileOrigin {
+	pub(crate) fn new(loaded_text: String, did_run_updaters: bool) -> Self {
+		Self {
+			disk_hash: hash_text(&loaded_text),
+			pre_migration_text: did_run_updaters.then(|| loaded_text.clone()),
+			disk_text: loaded_text,
+		}
+	}
+}
+
+pub(crate) fn hash_text(text: &str) -> u64 {
+	let mut hasher = DefaultHasher::new();
+	text.hash(&mut hasher);
+	hasher.finish()
+}
+
+
+
+impl File {
+	
+	
+	
+	/// Reads and parses a file, then runs any needed updaters (same as `from_str()`)
+	/// 
+	/// If the file can't be read, the error is returned. Otherwise, the file is returned along with its parse errors (which don't stop the rest of the file from loading)
+	pub fn load<T>(path: impl AsRef<Path>, updater_fns: &[UpdaterFn<T>], args: &mut T) -> Result<(Self, DidRunUpdaters, Vec<FileError>), FileError> {
+		let path = path.as_ref();
+		let contents = read_file(path)?;
+		let (mut file, did_run_updaters, errors) = Self::from_str(&contents, updater_fns, args);
+		file.origin = Some(FileOrigin::new(contents, did_run_updaters));
+		Ok((file, did_run_updaters, parse_errors_for(path, errors)))
+	}
+	
+	/// Same as `load()`, but uses migrations instead of updater functions (same as `from_str_with_migrations()`)
+	pub fn load_with_migrations<T>(path: impl AsRef<Path>, migrations: &[&dyn Migration<T>], args: &mut T) -> Result<(Self, MigrationResult, Vec<FileError>), FileError> {
+		let path = path.as_ref();
+		let contents = read_file(path)?;
+		let (mut file, result, errors) = Self::from_str_with_migrations(&contents, migrations, args);
+		let did_run_updaters = result.as_ref().is_ok_and(MigrationReport::did_run_updaters);
+		file.origin = Some(FileOrigin::new(contents, did_run_updaters));
+		Ok((file, result, parse_errors_for(path, errors)))
+	}
+	
+	/// Same as `load()`, but also applies aliases once the updaters have run (same as `from_str_with_aliases()`)
+	pub fn load_with_aliases<T>(path: impl AsRef<Path>, updater_fns: &[UpdaterFn<T>], args: &mut T, aliases: &[KeyAlias]) -> Result<AliasedLoad, FileError> {
+		let (mut file, did_run_updaters, errors) = Self::load(path, updater_fns, args)?;
+		let warnings = file.apply_aliases(aliases.iter().cloned());
+		Ok((file, did_run_updaters, errors, warnings))
+	}
+	
+	/// Formats and writes the file without keeping a backup, see `save_with_options()`
+	pub fn save(&mut self, path: impl AsRef<Path>) -> Result<SaveReport, FileError> {
+		self.save_with_options(path, SaveOptions::default())
+	}
+	
+	/// Formats and writes the file, and returns any errors from formatting (see `to_str()`) along with any changes that were merged
+	/// 
+	/// If `options.merge_changes` is set and the file on disk has changed since this file was loaded or last saved (for example because the user edited it while the app was running), those changes are merged into this file first, setting by setting (see `merge_three_way()`). Settings that were changed on disk and in memory keep the in-memory value and are returned as conflicts. If the file on disk can't be parsed, nothing is written and the parse error is returned, so that the user's edits are never lost
+	/// 
+	/// The file is written atomically: the new text is written to a temporary file in the same directory, flushed to disk, then renamed over the old file, so a crash never leaves a partially written file behind. If the path is a symlink, the file it points to is replaced
+	pub fn save_with_options(&mut self, path: impl AsRef<Path>, options: SaveOptions) -> Result<SaveReport, FileError> {
+		let path = path.as_ref();
+		let io_error = |error: io::Error| FileError::Io { path: path.to_path_buf(), error };
+		let disk_text = match fs::read_to_string(path) {
+			Ok(disk_text) => Some(disk_text),
+			Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+			Err(err) => return Err(io_error(err)),
+		};
+		
+		let mut merged_changes = false;
+		let mut conflicts = vec!();
+		if options.merge_changes && let Some(origin) = &self.origin && let Some(disk_text) = &disk_text && hash_text(disk_text) != origin.disk_hash {
+			let (theirs, _version, mut parse_errors) = File::parse(disk_text);
+			if !parse_errors.is_empty() {
+				return Err(FileError::Parse { path: path.to_path_buf(), error: parse_errors.remove(0) });
+			}
+			let (base, _version, _parse_errors) = File::parse(&origin.disk_text);
+			let (merged, merge_conflicts) = File::merge_three_way(&base, self, &theirs);
+			*self = merged;
+			merged_changes = true;
+			conflicts = merge_conflicts;
+		}
+		
+		let (text, format_errors) = self.to_str();
+		match options.backup {
+			Backup::None => {}
+			Backup::PreviousVersion => {
+				if let Some(disk_text) = &disk_text {
+					write_atomic(&backup_path(path), disk_text).map_err(io_error)?;
+				}
+			}
+			Backup::PreMigration => {
+				if let Some(pre_migration_text) = self.origin.as_ref().and_then(|origin| origin.pre_migration_text.as_ref()) {
+					write_atomic(&backup_path(path), pre_migration_text).map_err(io_error)?;
+				}
+			}
+		}
+		write_atomic(path, &text).map_err(io_error)?;
+		let origin = self.origin.get_or_insert_with(FileOrigin::default);
+		origin.disk_hash = hash_text(&text);
+		origin.disk_text = text;
+		if options.backup == Backup::PreMigration {
+			origin.pre_migration_text = None;
+		}
+		Ok(SaveReport {
+			format_errors,
+			merged_changes,
+			conflicts,
+		})
+	}
+	
+	
+	
+}
+
+
+
+fn read_file(path: &Path) -> Result<String, FileError> {
+	fs::read_to_string(path).map_err(|error| FileError::Io { path: path.to_path_buf(), error })
+}
+
+fn parse_errors_for(path: &Path, errors: Vec<ParseEntryError>) -> Vec<FileError> {
+	errors.into_iter().map(|error| FileError::Parse { path: path.to_path_buf(), error }).collect()
+}
+
+static NEXT_SAVE_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Writes a file by writing to a temporary file in the same directory, flushing it to disk, then renaming it over the original file
+pub(crate) fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+	let path = match fs::symlink_metadata(path) {
+		Ok(metadata) if metadata.file_type().is_symlink() => fs::canonicalize(path)?,
+		_ => path.to_path_buf(),
+	};
+	let Some(file_name) = path.file_name() else {
+		return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("\"{}\" is not a file path", path.display())));
+	};
+	let mut temp_name = OsString::from(".");
+	temp_name.push(file_name);
+	// the counter keeps saves from different threads apart, since they share a process id
+	let save_id = NEXT_SAVE_ID.fetch_add(1, Ordering::Relaxed);
+	temp_name.push(format!(".{}.{save_id}.tmp", std::process::id()));
+	let temp_path = path.with_file_name(temp_name);
+	
+	let result = (|| {
+		let mut temp_file = fs::File::create(&temp_path)?;
+		temp_file.write_all(contents.as_bytes())?;
+		if let Ok(metadata) = fs::metadata(&path) {
+			temp_file.set_permissions(metadata.permissions())?;
+		}
+		temp_file.sync_all()?;
+		drop(temp_file);
+		fs::rename(&temp_path, &path)
+	})();
+	if result.is_err() {
+		let _ = fs::remove_file(&temp_path);
+	}
+	result?;
+	
+	// the directory also needs to be synced for the rename to survive a crash, but this isn't supported everywhere
+	let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+	if let Ok(dir) = fs::File::open(dir) {
+		let _ = dir.sync_all();
+	}
+	Ok(())
+}
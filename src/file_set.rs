@@ -0,0 +1,280 @@
+use crate::*;
+use std::{collections::HashMap, path::{Path, PathBuf}};
+
+
+
+/// A single file within a `FileSet`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceFile {
+	/// Path of the file
+	pub path: PathBuf,
+	/// Settings of the file
+	pub file: File,
+	/// Paths of the files that were loaded by each of this file's include directives, keyed by the path or pattern of the include
+	pub includes: HashMap<String, Vec<PathBuf>>,
+	/// Text that was last loaded or saved, used to only write files that have changed
+	pub(crate) saved_text: String,
+}
+
+impl SourceFile {
+	/// Creates a source file that counts as unchanged
+	pub(crate) fn new(path: PathBuf, file: File) -> Self {
+		let saved_text = file.to_str().0;
+		Self {
+			path,
+			file,
+			includes: HashMap::new(),
+			saved_text,
+		}
+	}
+}
+
+
+
+/// Decides which file's value is used when several files in a `FileSet` define the same setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyConflictRule {
+	/// The first file that defines a setting is used
+	FirstFileWins,
+	/// The last file that defines a setting is used
+	LastFileWins,
+}
+
+
+
+/// Describes which file a setting came from
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KeySource {
+	/// Path of the file that supplied the value
+	pub path: PathBuf,
+	/// Line number (using 1-based indexing) of the setting within the file, or `None` if the setting isn't in the file's layout
+	pub line: Option<usize>,
+}
+
+impl std::fmt::Display for KeySource {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.line {
+			Some(line) => write!(f, "line {line} of \"{}\"", self.path.display()),
+			None => write!(f, "\"{}\"", self.path.display()),
+		}
+	}
+}
+
+
+
+/// Settings that are spread across several files, where each setting remembers which file it came from so that saving writes it back to that file
+/// 
+/// The first file is the root file, which is where new settings are added
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileSet {
+	/// Every file, starting with the root file
+	pub files: Vec<SourceFile>,
+	/// Which file is used when several files define the same setting
+	pub conflict_rule: KeyConflictRule,
+}
+
+impl FileSet {
+	
+	
+	
+	/// Creates a set that only holds a root file (which counts as changed, so `save()` will write it)
+	pub fn new(path: impl Into<PathBuf>, file: File, conflict_rule: KeyConflictRule) -> Self {
+		let mut root = SourceFile::new(path.into(), file);
+		root.saved_text = String::new();
+		Self {
+			files: vec!(root),
+			conflict_rule,
+		}
+	}
+	
+	/// Returns the root file
+	pub fn root(&self) -> &File {
+		&self.files[0].file
+	}
+	
+	/// Returns the root file as mut
+	pub fn root_mut(&mut self) -> &mut File {
+		&mut self.files[0].file
+	}
+	
+	/// Returns the file at a path
+	pub fn file(&self, path: impl AsRef<Path>) -> Option<&File> {
+		let path = path.as_ref();
+		self.files.iter().find(|source| source.path == path).map(|source| &source.file)
+	}
+	
+	/// Returns the file at a path as mut
+	pub fn file_mut(&mut self, path: impl AsRef<Path>) -> Option<&mut File> {
+		let path = path.as_ref();
+		self.files.iter_mut().find(|source| source.path == path).map(|source| &mut source.file)
+	}
+	
+	/// Returns the indices of the files in the order that they are checked for settings
+	fn priority_order(&self) -> Vec<usize> {
+		match self.conflict_rule {
+			KeyConflictRule::FirstFileWins => (0..self.files.len()).collect(),
+			KeyConflictRule::LastFileWins => (0..self.files.len()).rev().collect(),
+		}
+	}
+	
+	pub(crate) fn source_index(&self, key: &str) -> Option<usize> {
+		self.priority_order().into_iter().find(|i| {
+			let file = &self.files[*i].file;
+			file.values.contains_key(file.resolve_key(key))
+		})
+	}
+	
+	
+	
+	/// Reports a file that doesn't have a version, or that has a different version than the root file
+	pub(crate) fn check_version(&self, path: &Path, version: Option<usize>) -> Option<SourceFileError> {
+		match (version, self.files.first()) {
+			(None, _) => Some(SourceFileError::new(path, Some(1), "Could not find version")),
+			(Some(version), Some(root)) if version != root.file.version => {
+				Some(SourceFileError::new(path, Some(1), format!("Format {version} does not match the root file's format ({})", root.file.version)))
+			}
+			_ => None,
+		}
+	}
+	
+	
+	
+	/// Returns which file (and which line of that file) supplied the value of a setting
+	pub fn source_of(&self, key: impl AsRef<str>) -> Option<KeySource> {
+		let key = key.as_ref();
+		let source = &self.files[self.source_index(key)?];
+		Some(KeySource {
+			path: source.path.clone(),
+			line: source.file.line_of(key),
+		})
+	}
+	
+	/// Returns every file that defines a setting, starting with the one whose value is used
+	pub fn sources_of(&self, key: impl AsRef<str>) -> Vec<KeySource> {
+		let key = key.as_ref();
+		self.priority_order().into_iter()
+			.map(|i| &self.files[i])
+			.filter(|source| source.file.values.contains_key(source.file.resolve_key(key)))
+			.map(|source| KeySource { path: source.path.clone(), line: source.file.line_of(key) })
+			.collect()
+	}
+	
+	/// Returns the value of a setting
+	pub fn value(&self, key: impl AsRef<str>) -> Option<&Value> {
+		let key = key.as_ref();
+		let file = &self.files[self.source_index(key)?].file;
+		file.values.get(file.resolve_key(key))
+	}
+	
+	/// Gets the value of a setting as any type that implements `FromValue`, using the coercion settings and aliases of the file that the setting came from
+	/// 
+	/// Types made of multiple settings (like `Vec<T>`) are taken entirely from one file
+	pub fn get<T: FromValue>(&self, key: impl AsRef<str>) -> Result<T, RetrieveSettingError> {
+		let key = key.as_ref();
+		if let Some(i) = self.source_index(key) {
+			return self.files[i].file.get::<T>(key);
+		}
+		for i in self.priority_order() {
+			match self.files[i].file.get::<T>(key) {
+				Err(RetrieveSettingError::Missing { .. }) => continue,
+				result => return result,
+			}
+		}
+		Err(RetrieveSettingError::new_missing(key))
+	}
+	
+	/// Sets the value of a setting in the file that it came from (or in the root file, if the setting is new), and returns the previous value
+	pub fn set(&mut self, key: impl AsRef<str>, value: impl IntoValue) -> Option<Value> {
+		let key = key.as_ref();
+		let i = self.source_index(key).unwrap_or(0);
+		self.files[i].file.set(key, value)
+	}
+	
+	/// Removes a setting from the file that it came from (along with its layout entry), and returns its value
+	/// 
+	/// If other files also define the setting, the setting will then be taken from one of them
+	pub fn remove(&mut self, key: impl AsRef<str>) -> Option<Value> {
+		let key = key.as_ref();
+		let i = self.source_index(key)?;
+		let file = &mut self.files[i].file;
+		let key = file.resolve_key(key).to_string();
+		if let Some(layout_index) = file.layout_index(&key) {
+			file.layout.remove(layout_index);
+		}
+		file.values.remove(&key)
+	}
+	
+	/// Combines every file into a single file, which is useful for things like `Schema::validate()` and `File::reader()`
+	/// 
+	/// Included files are placed where they are included, and any other files are placed after the root file. The version is the root file's version
+	pub fn to_file(&self) -> File {
+		let mut output = File::new(self.root().version);
+		let mut visited = vec!(false; self.files.len());
+		for i in 0..self.files.len() {
+			if !visited[i] {
+				self.append_layout(i, &mut output, &mut visited);
+			}
+		}
+		for source in &self.files {
+			for key in source.file.values.keys() {
+				if output.values.contains_key(key) {continue;}
+				let Some(value) = self.value(key) else {continue;};
+				output.values.insert(key.clone(), value.clone());
+			}
+		}
+		output
+	}
+	
+	fn append_layout(&self, i: usize, output: &mut File, visited: &mut [bool]) {
+		visited[i] = true;
+		let source = &self.files[i];
+		for entry in &source.file.layout {
+			match entry {
+				LayoutEntry::Include (pattern) => {
+					let Some(paths) = source.includes.get(pattern) else {continue;};
+					for path in paths {
+						let Some(included_i) = self.files.iter().position(|other| other.path == *path) else {continue;};
+						if !visited[included_i] {
+							self.append_layout(included_i, output, visited);
+						}
+					}
+				}
+				LayoutEntry::Key (key) => {
+					if self.source_index(key) == Some(i) && output.layout_index(key).is_none() {
+						output.layout.push(entry.clone());
+					}
+				}
+				_ => output.layout.push(entry.clone()),
+			}
+		}
+	}
+	
+	
+	
+	/// Returns the paths of every file that has changed since it was loaded or last saved
+	pub fn changed_files(&self) -> Vec<&Path> {
+		self.files.iter().filter(|source| source.file.to_str().0 != source.saved_text).map(|source| &*source.path).collect()
+	}
+	
+	/// Writes every file that has changed since it was loaded or last saved, so that each setting is saved to the file it came from
+	/// 
+	/// Files that fail to save are reported and the rest are still saved
+	pub fn save(&mut self, loader: &impl FileLoader) -> Vec<SourceFileError> {
+		let mut errors = vec!();
+		for source in &mut self.files {
+			let (text, format_errors) = source.file.to_str();
+			for err in format_errors {
+				errors.push(SourceFileError::new(&source.path, None, err.to_string()));
+			}
+			if text == source.saved_text {continue;}
+			match loader.write(&source.path, &text) {
+				Ok(()) => source.saved_text = text,
+				Err(err) => errors.push(SourceFileError::new(&source.path, None, format!("Could not write file: {err}"))),
+			}
+		}
+		errors
+	}
+	
+	
+	
+}
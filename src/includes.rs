@@ -0,0 +1,158 @@
+use crate::*;
+use std::path::{Path, PathBuf};
+
+
+
+/// Options for `FileSet::load_with_includes()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IncludeOptions {
+	/// How deeply includes can be nested, where files included by the root file are at depth 1 (default: 8)
+	pub max_depth: usize,
+}
+
+impl Default for IncludeOptions {
+	fn default() -> Self {
+		Self {
+			max_depth: 8,
+		}
+	}
+}
+
+
+
+impl FileSet {
+	
+	/// Loads a file along with every file that it includes, where includes are written as `include "network.ecf"` or `include "conf.d/*.ecf"`
+	/// 
+	/// Include paths are relative to the including file, and patterns can use `*` and `?` in the file name (matching files are included in lexical order). Every included file needs its own format line, and every setting should only be defined once across all files (duplicates are reported, and the first definition is used). Include cycles, includes that are nested too deeply, and files that are included more than once are reported and skipped
+	/// 
+	/// The returned errors include every parse error from every file, but if the root file can't be read then only that error is returned
+	pub fn load_with_includes(path: impl AsRef<Path>, loader: &impl FileLoader, options: IncludeOptions) -> Result<(Self, Vec<SourceFileError>), SourceFileError> {
+		let path = crate::utils::normalize_path(path.as_ref());
+		let mut set = Self {
+			files: vec!(),
+			conflict_rule: KeyConflictRule::FirstFileWins,
+		};
+		let mut errors = vec!();
+		let mut stack = vec!();
+		if let Err(message) = set.load_included_file(&path, loader, options, &mut stack, &mut errors) {
+			return Err(SourceFileError::new(path, None, message));
+		}
+		errors.append(&mut set.find_duplicate_keys());
+		Ok((set, errors))
+	}
+	
+	fn load_included_file(&mut self, path: &Path, loader: &impl FileLoader, options: IncludeOptions, stack: &mut Vec<PathBuf>, errors: &mut Vec<SourceFileError>) -> Result<(), String> {
+		let contents = loader.read(path).map_err(|err| format!("Could not read file: {err}"))?;
+		let (mut file, version, parse_errors) = File::parse(contents);
+		let (includes, parse_errors) = parse_includes(&mut file, parse_errors);
+		for err in parse_errors {
+			errors.push(SourceFileError::new(path, Some(err.line), err.message));
+		}
+		if let Some(err) = self.check_version(path, version) {
+			errors.push(err);
+		}
+		let file_i = self.files.len();
+		self.files.push(SourceFile::new(path.to_path_buf(), file));
+		
+		stack.push(path.to_path_buf());
+		for (pattern, line) in includes {
+			let error = |message: String| SourceFileError::new(path, Some(line), message);
+			let included_paths = match resolve_include(path, &pattern, loader) {
+				Ok(included_paths) => included_paths,
+				Err(message) => {
+					errors.push(error(message));
+					continue;
+				}
+			};
+			for included_path in included_paths {
+				if stack.len() > options.max_depth {
+					errors.push(error(format!("Includes cannot be nested more than {} levels deep", options.max_depth)));
+					break;
+				}
+				if let Some(cycle_start) = stack.iter().position(|other| *other == included_path) {
+					let cycle = stack[cycle_start..].iter().chain([&included_path]).map(|path| format!("\"{}\"", path.display())).collect::<Vec<_>>();
+					errors.push(error(format!("Include cycle found: {}", cycle.join(" -> "))));
+					continue;
+				}
+				if self.files.iter().any(|source| source.path == included_path) {
+					errors.push(error(format!("\"{}\" is already included", included_path.display())));
+					continue;
+				}
+				match self.load_included_file(&included_path, loader, options, stack, errors) {
+					Ok(()) => self.files[file_i].includes.entry(pattern.clone()).or_default().push(included_path),
+					Err(message) => errors.push(error(format!("Could not include \"{}\": {message}", included_path.display()))),
+				}
+			}
+		}
+		stack.pop();
+		Ok(())
+	}
+	
+	/// Reports every setting that is defined in more than one file
+	pub(crate) fn find_duplicate_keys(&self) -> Vec<SourceFileError> {
+		let mut errors = vec!();
+		for (i, source) in self.files.iter().enumerate() {
+			let mut keys = source.file.values.keys().collect::<Vec<_>>();
+			keys.sort();
+			for key in keys {
+				let Some(winner_i) = self.source_index(key) else {continue;};
+				if winner_i == i {continue;}
+				let message = format!("Key \"{key}\" is already defined in \"{}\", so this definition is ignored", self.files[winner_i].path.display());
+				errors.push(SourceFileError::new(&source.path, source.file.line_of(key), message));
+			}
+		}
+		errors
+	}
+	
+}
+
+
+
+/// Turns the comments that were created for include lines back into include entries, and returns each include's path / pattern and line number along with the remaining parse errors
+fn parse_includes(file: &mut File, parse_errors: Vec<ParseEntryError>) -> (Vec<(String, usize)>, Vec<ParseEntryError>) {
+	let line_numbers = file.layout_line_numbers();
+	let mut includes = vec!();
+	let mut remaining_errors = vec!();
+	for err in parse_errors {
+		let layout_index = line_numbers.iter().position(|line| *line == err.line);
+		let pattern = layout_index.and_then(|layout_index| match &file.layout[layout_index] {
+			LayoutEntry::Comment (comment) => parse_include_line(comment.trim()).map(str::to_string),
+			_ => None,
+		});
+		match (layout_index, pattern) {
+			(Some(layout_index), Some(pattern)) => {
+				file.layout[layout_index] = LayoutEntry::Include (pattern.clone());
+				includes.push((pattern, err.line));
+			}
+			_ => remaining_errors.push(err),
+		}
+	}
+	(includes, remaining_errors)
+}
+
+fn parse_include_line(line: &str) -> Option<&str> {
+	let rest = line.strip_prefix("include")?;
+	if !rest.starts_with(char::is_whitespace) {return None;}
+	let pattern = rest.trim().strip_prefix('"')?.strip_suffix('"')?;
+	if pattern.is_empty() {return None;}
+	Some(pattern)
+}
+
+/// Returns the paths of every file that an include refers to
+fn resolve_include(including_path: &Path, pattern: &str, loader: &impl FileLoader) -> Result<Vec<PathBuf>, String> {
+	let dir = including_path.parent().unwrap_or(Path::new(""));
+	let target = crate::utils::normalize_path(&dir.join(pattern));
+	let file_name = target.file_name().and_then(|name| name.to_str()).unwrap_or("");
+	if !file_name.contains(['*', '?']) {
+		return Ok(vec!(target));
+	}
+	let target_dir = target.parent().unwrap_or(Path::new(""));
+	let candidates = loader.list_dir(target_dir).map_err(|err| format!("Could not list directory \"{}\": {err}", target_dir.display()))?;
+	let mut output = candidates.into_iter()
+		.filter(|candidate| candidate.file_name().and_then(|name| name.to_str()).is_some_and(|name| crate::utils::glob_match(file_name, name)))
+		.map(|candidate| crate::utils::normalize_path(&candidate))
+		.collect::<Vec<_>>();
+	output.sort();
+	Ok(output)
+}
@@ -0,0 +1,116 @@
+use crate::*;
+
+
+
+/// Escapes text so that it's kept exactly as it is when it's expanded by `File::get_interpolated()` (every `${` is replaced with `$${`)
+pub fn escape_interpolation(text: impl AsRef<str>) -> String {
+	text.as_ref().replace("${", "$${")
+}
+
+
+
+impl File {
+	
+	/// Gets the value of a string setting with every reference expanded, where `${key}` is replaced with the value of another setting and `${env:NAME}` is replaced with an environment variable
+	/// 
+	/// Referenced settings are expanded too, so `cache dir: "${data dir}/cache"` can be used by another setting as `"${cache dir}/images"`. Non-string settings are inserted as they would be written (so `${port}` becomes `8080`), and empty settings are inserted as nothing. To write a literal `${`, use `$${`
+	/// 
+	/// This doesn't change the file, so `to_str()` still writes the unexpanded text
+	pub fn get_interpolated(&self, key: impl AsRef<str>) -> Result<String, InterpolationError> {
+		self.get_interpolated_with_env(key, |name| std::env::var(name).ok())
+	}
+	
+	/// Same as `get_interpolated()`, but uses `env` to look up environment variables (which is mainly useful for testing)
+	pub fn get_interpolated_with_env(&self, key: impl AsRef<str>, env: impl Fn(&str) -> Option<String>) -> Result<String, InterpolationError> {
+		let key = self.resolve_key(key.as_ref());
+		match self.values.get(key) {
+			None => Err(self.interpolation_error(key, "Setting does not exist")),
+			Some(Value::String (_)) => self.expand(key, &env, &mut vec!()),
+			Some(value) => Err(self.interpolation_error(key, format!("Setting was expected to be a String, but is {}", value.type_as_singular_string()))),
+		}
+	}
+	
+	/// Returns a copy of this file where every string setting has its references expanded (see `get_interpolated()`), so that the normal getters return expanded values
+	/// 
+	/// Settings that fail to expand are kept as they are, and the errors are returned in order of key
+	pub fn interpolated(&self) -> (File, Vec<InterpolationError>) {
+		self.interpolated_with_env(|name| std::env::var(name).ok())
+	}
+	
+	/// Same as `interpolated()`, but uses `env` to look up environment variables (which is mainly useful for testing)
+	pub fn interpolated_with_env(&self, env: impl Fn(&str) -> Option<String>) -> (File, Vec<InterpolationError>) {
+		let mut output = self.clone();
+		let mut errors = vec!();
+		let mut keys = self.values.iter().filter(|(_key, value)| matches!(value, Value::String (string) if string.contains('$'))).map(|(key, _value)| key).collect::<Vec<_>>();
+		keys.sort();
+		for key in keys {
+			match self.expand(key, &env, &mut vec!()) {
+				Ok(expanded) => {output.values.insert(key.clone(), Value::String (expanded));}
+				Err(err) => errors.push(err),
+			}
+		}
+		(output, errors)
+	}
+	
+	fn expand<'a>(&'a self, key: &'a str, env: &impl Fn(&str) -> Option<String>, stack: &mut Vec<&'a str>) -> Result<String, InterpolationError> {
+		let text = match self.values.get(key) {
+			Some(Value::String (text)) => text,
+			Some(Value::Empty) => return Ok(String::new()),
+			Some(value) => return Ok(value.format()),
+			None => return Err(self.interpolation_error(key, "Setting does not exist")),
+		};
+		stack.push(key);
+		let mut output = String::new();
+		let mut rest = &**text;
+		while let Some(dollar_i) = rest.find('$') {
+			output += &rest[..dollar_i];
+			rest = &rest[dollar_i..];
+			if let Some(after) = rest.strip_prefix("$${") {
+				output += "${";
+				rest = after;
+				continue;
+			}
+			let Some(after) = rest.strip_prefix("${") else {
+				output.push('$');
+				rest = &rest[1..];
+				continue;
+			};
+			let Some(end_i) = after.find('}') else {
+				return Err(self.interpolation_error(key, "Could not find the end of a reference, references are written as \"${key}\" or \"${env:NAME}\" (and a literal \"${\" is written as \"$${\")"));
+			};
+			let reference = after[..end_i].trim();
+			rest = &after[end_i + 1 ..];
+			if let Some(name) = reference.strip_prefix("env:") {
+				let Some(env_value) = env(name.trim()) else {
+					return Err(self.interpolation_error(key, format!("Environment variable \"{}\" is not set", name.trim())));
+				};
+				output += &env_value;
+				continue;
+			}
+			if reference.is_empty() {
+				return Err(self.interpolation_error(key, "References cannot be empty"));
+			}
+			let referenced_key = self.resolve_key(reference);
+			if let Some(cycle_start) = stack.iter().position(|other| *other == referenced_key) {
+				let cycle = stack[cycle_start..].iter().chain([&referenced_key]).map(|key| format!("\"{key}\"")).collect::<Vec<_>>();
+				return Err(self.interpolation_error(key, format!("Reference cycle found: {}", cycle.join(" -> "))));
+			}
+			if !self.values.contains_key(referenced_key) {
+				return Err(self.interpolation_error(key, format!("Referenced setting \"{reference}\" does not exist")));
+			}
+			output += &self.expand(referenced_key, env, stack)?;
+		}
+		output += rest;
+		stack.pop();
+		Ok(output)
+	}
+	
+	fn interpolation_error(&self, key: &str, message: impl Into<String>) -> InterpolationError {
+		InterpolationError {
+			key: key.to_string(),
+			line: self.line_of(key),
+			message: message.into(),
+		}
+	}
+	
+}
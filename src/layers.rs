@@ -0,0 +1,218 @@
+use crate::*;
+use std::collections::BTreeSet;
+
+
+
+/// A single file within a `LayeredConfig`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigLayer {
+	/// Name used to refer to the layer (for example, "defaults" or "user")
+	pub name: String,
+	/// Layers with a higher priority override layers with a lower priority
+	pub priority: i32,
+	/// Settings of the layer
+	pub file: File,
+}
+
+
+
+/// Describes where the value of a setting came from
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Provenance {
+	/// Name of the layer that supplied the value
+	pub layer: String,
+	/// Line number (using 1-based indexing) of the setting within the layer's file, or `None` if the setting isn't in the layer's layout
+	pub line: Option<usize>,
+}
+
+impl std::fmt::Display for Provenance {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.line {
+			Some(line) => write!(f, "line {line} of layer \"{}\"", self.layer),
+			None => write!(f, "layer \"{}\"", self.layer),
+		}
+	}
+}
+
+
+
+/// Combines several files (for example: built-in defaults, system settings, user settings, project settings, and runtime overrides), where each setting is taken from the highest-priority layer that defines it
+/// 
+/// Layers are only ever edited individually, so saving a layer (with `to_str()`) only writes the changes that were made to that layer
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LayeredConfig {
+	/// Every layer, ordered from lowest to highest priority
+	layers: Vec<ConfigLayer>,
+}
+
+impl LayeredConfig {
+	
+	/// Suggested priority for built-in defaults
+	pub const DEFAULTS_PRIORITY: i32 = 0;
+	/// Suggested priority for system-wide settings (like `/etc/app/settings.ecf`)
+	pub const SYSTEM_PRIORITY: i32 = 100;
+	/// Suggested priority for per-user settings (like `~/.config/app/settings.ecf`)
+	pub const USER_PRIORITY: i32 = 200;
+	/// Suggested priority for project-local settings
+	pub const PROJECT_PRIORITY: i32 = 300;
+	/// Suggested priority for runtime overrides (like environment variables or command-line arguments)
+	pub const OVERRIDES_PRIORITY: i32 = 400;
+	
+	/// Creates a config with no layers
+	pub fn new() -> Self {
+		Self::default()
+	}
+	
+	/// Adds a layer and returns the previous layer with the same name (if there was one)
+	/// 
+	/// Layers with equal priorities are ordered by when they were added, so later layers win
+	pub fn add_layer(&mut self, name: impl Into<String>, priority: i32, file: File) -> Option<ConfigLayer> {
+		let name = name.into();
+		let previous = self.remove_layer(&name);
+		let index = self.layers.partition_point(|layer| layer.priority <= priority);
+		self.layers.insert(index, ConfigLayer { name, priority, file });
+		previous
+	}
+	
+	/// Same as `add_layer()`, but takes and returns `self` so layers can be chained
+	pub fn with_layer(mut self, name: impl Into<String>, priority: i32, file: File) -> Self {
+		self.add_layer(name, priority, file);
+		self
+	}
+	
+	/// Removes a layer and returns it
+	pub fn remove_layer(&mut self, name: &str) -> Option<ConfigLayer> {
+		let index = self.layers.iter().position(|layer| layer.name == name)?;
+		Some(self.layers.remove(index))
+	}
+	
+	/// Returns every layer, ordered from lowest to highest priority
+	pub fn layers(&self) -> &[ConfigLayer] {
+		&self.layers
+	}
+	
+	/// Returns the file of a layer
+	pub fn layer(&self, name: &str) -> Option<&File> {
+		self.layers.iter().find(|layer| layer.name == name).map(|layer| &layer.file)
+	}
+	
+	/// Returns the file of a layer as mut
+	pub fn layer_mut(&mut self, name: &str) -> Option<&mut File> {
+		self.layers.iter_mut().find(|layer| layer.name == name).map(|layer| &mut layer.file)
+	}
+	
+	
+	
+	/// Returns the highest-priority layer that defines a setting
+	pub fn source_layer(&self, key: impl AsRef<str>) -> Option<&ConfigLayer> {
+		let key = key.as_ref();
+		self.layers.iter().rev().find(|layer| layer.file.values.contains_key(layer.file.resolve_key(key)))
+	}
+	
+	/// Returns the value of a setting, taken from the highest-priority layer that defines it
+	pub fn value(&self, key: impl AsRef<str>) -> Option<&Value> {
+		let key = key.as_ref();
+		let layer = self.source_layer(key)?;
+		layer.file.values.get(layer.file.resolve_key(key))
+	}
+	
+	/// Returns which layer (and which line of that layer) supplied the value of a setting
+	pub fn provenance(&self, key: impl AsRef<str>) -> Option<Provenance> {
+		let key = key.as_ref();
+		let layer = self.source_layer(key)?;
+		Some(Provenance {
+			layer: layer.name.clone(),
+			line: layer.file.line_of(key),
+		})
+	}
+	
+	/// Returns every key that is defined in any layer, in sorted order
+	pub fn keys(&self) -> Vec<&str> {
+		let keys = self.layers.iter().flat_map(|layer| layer.file.values.keys().map(String::as_str)).collect::<BTreeSet<_>>();
+		keys.into_iter().collect()
+	}
+	
+	/// Combines every layer into a single file, which is useful for things like `Schema::validate()` and `File::reader()`
+	/// 
+	/// The layout is taken from the lowest-priority layer (usually the defaults), followed by any keys that it doesn't have. The version is the highest version of any layer
+	pub fn flatten(&self) -> File {
+		let mut output = File::new(self.layers.iter().map(|layer| layer.file.version).max().unwrap_or(0));
+		if let Some(lowest_layer) = self.layers.first() {
+			output.layout = lowest_layer.file.layout.clone();
+		}
+		for key in self.keys() {
+			let Some(value) = self.value(key) else {continue;};
+			output.set(key, value.clone());
+		}
+		output
+	}
+	
+	
+	
+	/// Gets the value of a setting from the highest-priority layer that defines it, as any type that implements `FromValue`
+	/// 
+	/// Each layer's own coercion settings and aliases are used. Types made of multiple settings (like `Vec<T>`) are taken entirely from one layer
+	pub fn get<T: FromValue>(&self, key: impl AsRef<str>) -> Result<T, RetrieveSettingError> {
+		self.get_from_layers(key.as_ref(), |file, key| file.get::<T>(key))
+	}
+	
+	/// Basically an assert for a setting key existing but being left empty
+	pub fn get_empty(&self, key: impl AsRef<str>) -> Result<(), RetrieveSettingError> {
+		self.get_from_layers(key.as_ref(), |file, key| file.get_empty(key))
+	}
+	
+	/// Gets the value of a setting as an int (or returns an error if the setting is missing or if it's holding the wrong type)
+	pub fn get_int(&self, key: impl AsRef<str>) -> Result<i64, RetrieveSettingError> {
+		self.get::<i64>(key)
+	}
+	
+	/// Gets the value of a setting as a float (or returns an error if the setting is missing or if it's holding the wrong type)
+	pub fn get_float(&self, key: impl AsRef<str>) -> Result<f64, RetrieveSettingError> {
+		self.get::<f64>(key)
+	}
+	
+	/// Gets the value of a setting as a float, but also allowing ints (or returns an error if the setting is missing or if it's holding the wrong type)
+	pub fn get_number(&self, key: impl AsRef<str>) -> Result<f64, RetrieveSettingError> {
+		self.get_from_layers(key.as_ref(), |file, key| file.get_number(key))
+	}
+	
+	/// Gets the value of a setting as a bool (or returns an error if the setting is missing or if it's holding the wrong type)
+	pub fn get_bool(&self, key: impl AsRef<str>) -> Result<bool, RetrieveSettingError> {
+		self.get::<bool>(key)
+	}
+	
+	/// Gets the value of a setting as a string (or returns an error if the setting is missing or if it's holding the wrong type)
+	pub fn get_str(&self, key: impl AsRef<str>) -> Result<&str, RetrieveSettingError> {
+		self.get_from_layers(key.as_ref(), |file, key| file.get_str(key))
+	}
+	
+	/// Gets the value of a setting as an enum (or returns an error if the setting is missing, if it isn't a string, or if it isn't one of the enum's spellings)
+	pub fn get_enum<T: EcfEnum>(&self, key: impl AsRef<str>) -> Result<T, RetrieveSettingError> {
+		self.get_from_layers(key.as_ref(), |file, key| file.get_enum::<T>(key))
+	}
+	
+	fn get_from_layers<'a, T>(&'a self, key: &str, get_fn: impl Fn(&'a File, &str) -> Result<T, RetrieveSettingError>) -> Result<T, RetrieveSettingError> {
+		for layer in self.layers.iter().rev() {
+			match get_fn(&layer.file, key) {
+				Err(RetrieveSettingError::Missing { .. }) => continue,
+				result => return result,
+			}
+		}
+		Err(RetrieveSettingError::new_missing(key))
+	}
+	
+	
+	
+	/// Sets the value of a setting within a single layer, and returns the previous value of the setting in that layer
+	pub fn set(&mut self, layer: &str, key: impl AsRef<str>, value: impl IntoValue) -> Result<Option<Value>, UnknownLayerError> {
+		let file = self.layer_mut(layer).ok_or_else(|| UnknownLayerError { layer: layer.to_string() })?;
+		Ok(file.set(key, value))
+	}
+	
+	/// Formats a single layer, which is how changes are saved without copying settings from the other layers
+	pub fn to_str(&self, layer: &str) -> Result<(String, Vec<FormatEntryError>), UnknownLayerError> {
+		let file = self.layer(layer).ok_or_else(|| UnknownLayerError { layer: layer.to_string() })?;
+		Ok(file.to_str())
+	}
+	
+}
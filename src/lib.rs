@@ -4,7 +4,7 @@
 //! - Support for strings, ints, float, bools, and comments
 //! - Elegant error handling, an invalid line in the middle won't ruin everything afterwards and loading then saving a file will always result in a valid ecf file (to see this in action, just run `cargo run --example main`)
 //! - 'Setting updater' functions have built-in support and encouragement
-//! - Almost no code (~500 sloc) and no dependencies (other than std)
+//! - Small codebase (~3,700 sloc) and no dependencies (other than std)
 //! 
 //! <br>
 //! 
@@ -114,6 +114,71 @@ pub use data::*;
 /// All errors defined by the crate
 pub mod errors;
 pub use errors::*;
+/// Conversions between setting values and rust types
+pub mod conversions;
+pub use conversions::*;
+/// Optional conversions for user-written values that have the wrong type
+pub mod coercion;
+pub use coercion::*;
+/// Retrieving many settings while collecting every error
+pub mod reader;
+pub use reader::*;
+/// Replacing invalid settings with defaults
+pub mod repair;
+pub use repair::*;
+/// Declarative descriptions of expected settings
+pub mod schema;
+pub use schema::*;
+/// Finding settings that aren't used
+pub mod unknown_keys;
+pub use unknown_keys::*;
+/// Renaming settings without a new format version
+pub mod aliases;
+pub use aliases::*;
+/// Migrating files between versions
+pub mod migration;
+pub use migration::*;
+/// Reusable migration steps
+pub mod migration_ops;
+pub use migration_ops::*;
+/// Versioning namespaces separately from the rest of the file
+pub mod namespaces;
+pub use namespaces::*;
+/// Combining several files into one set of settings
+pub mod layers;
+pub use layers::*;
+/// Overriding settings with environment variables
+pub mod env_overrides;
+pub use env_overrides::*;
+/// Overriding settings with command-line arguments
+pub mod cli_overrides;
+pub use cli_overrides::*;
+/// Merging changes made to different copies of a file
+pub mod merge;
+pub use merge::*;
+/// Listing the differences between two versions of a file
+pub mod diff;
+pub use diff::*;
+/// Loading and saving files on disk
+pub mod file_io;
+pub use file_io::*;
+/// Reading and writing files through a replaceable file system
+pub mod loader;
+pub use loader::*;
+/// Settings that are spread across several files
+pub mod file_set;
+pub use file_set::*;
+/// Including files from other files
+pub mod includes;
+pub use includes::*;
+/// Loading files along with `.d` directories of drop-in fragments
+pub mod drop_ins;
+pub use drop_ins::*;
+/// Expanding references to other settings inside strings
+pub mod interpolation;
+pub use interpolation::*;
+
+mod utils;
 
 
 
@@ -127,25 +192,11 @@ pub const fn i64(v: i64) -> Value {
 	Value::I64 (v)
 }
 
-/// Slightly easier way to create a new `ecf::Value::I64()`
-/// 
-/// This isn't marked as `const` because it gives an error for const trait functions
-pub fn to_i64(v: impl ToI64) -> Value {
-	Value::I64 (v.to_i64())
-}
-
 /// Slightly easier way to create a new `ecf::Value::F64()`
 pub const fn f64(v: f64) -> Value {
 	Value::F64 (v)
 }
 
-/// Slightly easier way to create a new `ecf::Value::F64()`
-/// 
-/// This isn't marked as `const` because it gives an error for const trait functions
-pub fn to_f64(v: impl ToF64) -> Value {
-	Value::F64 (v.to_f64())
-}
-
 /// Slightly easier way to create a new `ecf::Value::Bool()`
 pub const fn bool(v: bool) -> Value {
 	Value::Bool (v)
@@ -158,68 +209,9 @@ pub fn string(v: impl ToString) -> Value {
 	Value::String (v.to_string())
 }
 
-
-
-/// Used for `ecf::to_i64()` overloading
-pub trait ToI64 {
-	/// Purpose of trait
-	fn to_i64(self) -> i64;
-}
-
-macro_rules! impl_to_i64 {
-	($impl_type:ty) => {
-		impl ToI64 for $impl_type {
-			fn to_i64(self) -> i64 {
-				self as i64
-			}
-		}
-	};
-}
-
-impl_to_i64!(i8);
-impl_to_i64!(u8);
-impl_to_i64!(i16);
-impl_to_i64!(u16);
-impl_to_i64!(i32);
-impl_to_i64!(u32);
-impl_to_i64!(i64);
-impl_to_i64!(u64);
-impl_to_i64!(i128);
-impl_to_i64!(u128);
-impl_to_i64!(isize);
-impl_to_i64!(usize);
-impl_to_i64!(f32);
-impl_to_i64!(f64);
-
-
-
-/// Used for `ecf::to_f64()` overloading
-pub trait ToF64 {
-	/// Purpose of trait
-	fn to_f64(self) -> f64;
-}
-
-macro_rules! impl_to_f64 {
-	($impl_type:ty) => {
-		impl ToF64 for $impl_type {
-			fn to_f64(self) -> f64 {
-				self as f64
-			}
-		}
-	};
+/// Slightly easier way to create a new `ecf::Value` from any type that implements `IntoValue`
+/// 
+/// This isn't marked as `const` because it gives an error for const trait functions
+pub fn value(v: impl IntoValue) -> Value {
+	v.into_value()
 }
-
-impl_to_f64!(i8);
-impl_to_f64!(u8);
-impl_to_f64!(i16);
-impl_to_f64!(u16);
-impl_to_f64!(i32);
-impl_to_f64!(u32);
-impl_to_f64!(i64);
-impl_to_f64!(u64);
-impl_to_f64!(i128);
-impl_to_f64!(u128);
-impl_to_f64!(isize);
-impl_to_f64!(usize);
-impl_to_f64!(f32);
-impl_to_f64!(f64);
@@ -0,0 +1,97 @@
+use std::{collections::HashMap, io, path::{Path, PathBuf}, sync::Mutex};
+
+
+
+/// Used for reading and writing settings files, so that features which touch several files (like includes) can be used without a real file system
+pub trait FileLoader {
+	/// Returns the contents of a file
+	fn read(&self, path: &Path) -> io::Result<String>;
+	/// Returns the path of every file directly inside a directory, in any order
+	fn list_dir(&self, dir: &Path) -> io::Result<Vec<PathBuf>>;
+	/// Replaces the contents of a file (or creates it)
+	fn write(&self, path: &Path, contents: &str) -> io::Result<()>;
+}
+
+
+
+/// Loader that uses the real file system, where files are written atomically (see `File::save_with_options()`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FsLoader;
+
+impl FileLoader for FsLoader {
+	fn read(&self, path: &Path) -> io::Result<String> {
+		std::fs::read_to_string(path)
+	}
+	fn list_dir(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+		let mut output = vec!();
+		for entry in std::fs::read_dir(dir)? {
+			let entry = entry?;
+			if entry.file_type()?.is_file() {
+				output.push(entry.path());
+			}
+		}
+		Ok(output)
+	}
+	fn write(&self, path: &Path, contents: &str) -> io::Result<()> {
+		crate::file_io::write_atomic(path, contents)
+	}
+}
+
+
+
+/// Loader that keeps every file in memory, which is mainly useful for testing
+/// 
+/// Paths are compared exactly as given (after removing `.` and `..` components), so relative and absolute paths to the same file are different files
+#[derive(Debug, Default)]
+pub struct MemoryLoader {
+	files: Mutex<HashMap<PathBuf, String>>,
+}
+
+impl MemoryLoader {
+	/// Creates a loader with no files
+	pub fn new() -> Self {
+		Self::default()
+	}
+	/// Adds a file and returns `self`, so files can be chained
+	pub fn with_file(self, path: impl AsRef<Path>, contents: impl Into<String>) -> Self {
+		self.insert(path, contents);
+		self
+	}
+	/// Adds or replaces a file
+	pub fn insert(&self, path: impl AsRef<Path>, contents: impl Into<String>) {
+		let path = crate::utils::normalize_path(path.as_ref());
+		self.files.lock().unwrap().insert(path, contents.into());
+	}
+	/// Returns the contents of a file
+	pub fn get(&self, path: impl AsRef<Path>) -> Option<String> {
+		let path = crate::utils::normalize_path(path.as_ref());
+		self.files.lock().unwrap().get(&path).cloned()
+	}
+}
+
+impl Clone for MemoryLoader {
+	fn clone(&self) -> Self {
+		Self {
+			files: Mutex::new(self.files.lock().unwrap().clone()),
+		}
+	}
+}
+
+impl FileLoader for MemoryLoader {
+	fn read(&self, path: &Path) -> io::Result<String> {
+		self.get(path).ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("\"{}\" does not exist", path.display())))
+	}
+	fn list_dir(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+		let dir = crate::utils::normalize_path(dir);
+		let files = self.files.lock().unwrap();
+		let output = files.keys().filter(|path| path.parent() == Some(&*dir)).cloned().collect::<Vec<_>>();
+		if output.is_empty() && !files.keys().any(|path| path.starts_with(&dir)) {
+			return Err(io::Error::new(io::ErrorKind::NotFound, format!("\"{}\" does not exist", dir.display())));
+		}
+		Ok(output)
+	}
+	fn write(&self, path: &Path, contents: &str) -> io::Result<()> {
+		self.insert(path, contents);
+		Ok(())
+	}
+}
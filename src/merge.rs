@@ -0,0 +1,179 @@
+use crate::*;
+use std::collections::{BTreeSet, HashMap};
+
+
+
+/// A setting that was changed differently in two versions of a file, found while merging
+/// 
+/// `None` means that the setting is missing in that version
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeConflict {
+	/// Key of the setting
+	pub key: String,
+	/// Value in the common ancestor of both versions
+	pub base: Option<Value>,
+	/// Value in our version
+	pub ours: Option<Value>,
+	/// Value in their version
+	pub theirs: Option<Value>,
+}
+
+/// What `File::merge_defaults()` does with settings that the user has but that were removed from the defaults
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RemovedDefaults {
+	/// Remove the setting from the user's file
+	#[default]
+	Drop,
+	/// Replace the setting with a comment holding it (like `# key: value`), so the user can still see what it was set to
+	CommentOut,
+}
+
+
+
+impl std::fmt::Display for MergeConflict {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let describe = |value: &Option<Value>| match value {
+			Some(value) => crate::utils::format_short(value),
+			None => String::from("missing"),
+		};
+		write!(f, "Setting '{}' was changed from {} to {} in one version and to {} in the other", self.key, describe(&self.base), describe(&self.ours), describe(&self.theirs))
+	}
+}
+
+
+
+impl File {
+	
+	/// Merges the changes made in two versions of a file (`ours` and `theirs`) since their common ancestor (`base`), setting by setting
+	/// 
+	/// Each setting takes whichever side changed it (including adding or removing it). When both sides changed a setting in different ways, our value is kept and the setting is listed as a conflict. The layout starts as their layout, then our removed comments are removed and our added settings and comments are inserted after the entry that they follow in our layout. The version and other properties are taken from `ours`
+	pub fn merge_three_way(base: &File, ours: &File, theirs: &File) -> (File, Vec<MergeConflict>) {
+		let (values, conflicts) = merge_values(base, ours, theirs);
+		let mut output = ours.clone();
+		output.layout = merge_layouts(base, ours, theirs, &values);
+		output.values = values;
+		(output, conflicts)
+	}
+	
+	/// Merges a new version of an app's default settings into a user's settings file, where `old_defaults` are the defaults that the user's file started from
+	/// 
+	/// Settings that the user changed keep the user's value, and settings that the user didn't change get the new default value. The user also keeps their own settings and comments, and gets every setting and comment that was added to the defaults. Settings that were removed from the defaults are dropped or commented out (see `RemovedDefaults`). The layout follows the new defaults, with the user's additions placed after the entry that they follow in the user's file
+	/// 
+	/// Settings that were changed by the user and in the defaults are returned as conflicts, as are settings that the user changed but that were removed from the defaults. The returned file has the version of `new_defaults`, and all three files should be in the same format (so the user's file should be migrated first if the format changed)
+	pub fn merge_defaults(old_defaults: &File, new_defaults: &File, user: &File, removed: RemovedDefaults) -> (File, Vec<MergeConflict>) {
+		let mut user = user.clone();
+		let mut conflicts = vec!();
+		let mut removed_keys = old_defaults.values.keys().filter(|key| !new_defaults.values.contains_key(*key) && user.values.contains_key(*key)).collect::<Vec<_>>();
+		removed_keys.sort();
+		for key in removed_keys {
+			let Some(value) = user.values.remove(key) else {continue;};
+			if old_defaults.values.get(key) != Some(&value) {
+				conflicts.push(MergeConflict {
+					key: key.clone(),
+					base: old_defaults.values.get(key).cloned(),
+					ours: Some(value.clone()),
+					theirs: None,
+				});
+			}
+			let Some(index) = user.layout_index(key) else {continue;};
+			match removed {
+				RemovedDefaults::Drop => {user.layout.remove(index);}
+				RemovedDefaults::CommentOut => user.layout[index] = LayoutEntry::Comment (commented_out_setting(key, &value)),
+			}
+		}
+		
+		let (mut output, mut merge_conflicts) = File::merge_three_way(old_defaults, &user, new_defaults);
+		output.version = new_defaults.version;
+		conflicts.append(&mut merge_conflicts);
+		conflicts.sort_by(|a, b| a.key.cmp(&b.key));
+		(output, conflicts)
+	}
+	
+}
+
+
+
+fn merge_values(base: &File, ours: &File, theirs: &File) -> (HashMap<String, Value>, Vec<MergeConflict>) {
+	let keys = base.values.keys().chain(ours.values.keys()).chain(theirs.values.keys()).collect::<BTreeSet<_>>();
+	let mut values = HashMap::new();
+	let mut conflicts = vec!();
+	for key in keys {
+		let base_value = base.values.get(key);
+		let our_value = ours.values.get(key);
+		let their_value = theirs.values.get(key);
+		let merged_value = if our_value == their_value || their_value == base_value {
+			our_value
+		} else if our_value == base_value {
+			their_value
+		} else {
+			conflicts.push(MergeConflict {
+				key: key.clone(),
+				base: base_value.cloned(),
+				ours: our_value.cloned(),
+				theirs: their_value.cloned(),
+			});
+			our_value
+		};
+		if let Some(value) = merged_value {
+			values.insert(key.clone(), value.clone());
+		}
+	}
+	(values, conflicts)
+}
+
+
+
+fn merge_layouts(base: &File, ours: &File, theirs: &File, values: &HashMap<String, Value>) -> Vec<LayoutEntry> {
+	let mut output = theirs.layout.clone();
+	
+	// comments that we removed
+	for entry in &base.layout {
+		if !matches!(entry, LayoutEntry::Comment (_) | LayoutEntry::Include (_)) || ours.layout.contains(entry) {continue;}
+		if let Some(index) = output.iter().position(|other| other == entry) {
+			output.remove(index);
+		}
+	}
+	
+	// entries that we added, each placed after the entry that it follows in our layout
+	let is_addition = |entry: &LayoutEntry| match entry {
+		LayoutEntry::Empty => false,
+		LayoutEntry::Key (key) => !base.values.contains_key(key) && !theirs.values.contains_key(key),
+		_ => !base.layout.contains(entry) && !theirs.layout.contains(entry),
+	};
+	let mut prev_index = None;
+	for (i, entry) in ours.layout.iter().enumerate() {
+		let is_added_empty_line = *entry == LayoutEntry::Empty && ours.layout.get(i + 1).is_some_and(is_addition);
+		if is_addition(entry) || is_added_empty_line {
+			let index = prev_index.map_or(0, |prev_index| prev_index + 1);
+			output.insert(index, entry.clone());
+			prev_index = Some(index);
+		} else if *entry != LayoutEntry::Empty && let Some(index) = output.iter().position(|other| other == entry) {
+			prev_index = Some(index);
+		}
+	}
+	
+	// settings that were removed, settings that are listed twice, and settings that aren't listed
+	let mut listed_keys = BTreeSet::new();
+	output.retain(|entry| match entry {
+		LayoutEntry::Key (key) => values.contains_key(key) && listed_keys.insert(key.clone()),
+		_ => true,
+	});
+	let mut unlisted_keys = values.keys().filter(|key| !listed_keys.contains(*key)).collect::<Vec<_>>();
+	unlisted_keys.sort();
+	for key in unlisted_keys {
+		output.push(LayoutEntry::Key (key.clone()));
+	}
+	output
+}
+
+
+
+fn commented_out_setting(key: &str, value: &Value) -> String {
+	let formatted = value.format();
+	let formatted = formatted.trim_end_matches('\n');
+	if formatted.contains('\n') {
+		format!("{key}: {formatted}")
+	} else {
+		format!(" {key}: {formatted}")
+	}
+}
@@ -0,0 +1,359 @@
+use crate::*;
+
+
+
+/// Data given to each migration step
+#[derive(Debug)]
+pub struct MigrationContext<'a, T> {
+	/// Version that this step migrates from
+	pub from_version: usize,
+	/// Version that this step migrates to
+	pub to_version: usize,
+	/// Generic data passed to every migration step
+	pub args: &'a mut T,
+}
+
+impl<T> MigrationContext<'_, T> {
+	/// Creates an error for this migration step
+	pub fn error(&self, message: impl Into<String>) -> MigrationError {
+		MigrationError::new(self.from_version, self.to_version, message)
+	}
+}
+
+
+
+/// A single step of migrating a file from one version to the next
+/// 
+/// Unlike `UpdaterFn`, migrations can see and edit the entire file (including the layout), and they can fail. This is implemented for every closure / function with the signature `Fn(&mut File, &mut MigrationContext<T>) -> Result<(), MigrationError>`
+pub trait Migration<T> {
+	/// Purpose of trait
+	fn migrate(&self, file: &mut File, context: &mut MigrationContext<T>) -> Result<(), MigrationError>;
+}
+
+impl<T, F: Fn(&mut File, &mut MigrationContext<T>) -> Result<(), MigrationError>> Migration<T> for F {
+	fn migrate(&self, file: &mut File, context: &mut MigrationContext<T>) -> Result<(), MigrationError> {
+		(self)(file, context)
+	}
+}
+
+
+
+/// Output type for `File::from_str_with_migrations()` and `File::load_with_migrations()`
+pub type MigrationResult = Result<MigrationReport, MigrationError>;
+
+
+
+/// Describes what changed while migrating a file, returned by `File::migrate()`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationReport {
+	/// Version of the file before migrating
+	pub from_version: usize,
+	/// Version of the file after migrating
+	pub to_version: usize,
+	/// Every step that ran, in order
+	pub steps: Vec<MigrationStepReport>,
+}
+
+impl MigrationReport {
+	/// Returns whether any migration steps ran
+	pub fn did_run_updaters(&self) -> DidRunUpdaters {
+		!self.steps.is_empty()
+	}
+}
+
+impl std::fmt::Display for MigrationReport {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		if self.steps.is_empty() {
+			return write!(f, "Settings are already at format {}", self.to_version);
+		}
+		write!(f, "Settings were migrated from format {} to format {}:", self.from_version, self.to_version)?;
+		for step in &self.steps {
+			write!(f, "\n  format {} -> {}:", step.from_version, step.to_version)?;
+			if step.changes.is_empty() {
+				write!(f, " no settings changed")?;
+			}
+			for change in &step.changes {
+				write!(f, "\n    {change}")?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Describes what changed during a single migration step
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationStepReport {
+	/// Version that the step migrated from
+	pub from_version: usize,
+	/// Version that the step migrated to
+	pub to_version: usize,
+	/// Every setting that was changed, ordered by key
+	pub changes: Vec<KeyChange>,
+}
+
+/// Describes a change to a single setting
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyChange {
+	/// A setting was added
+	Added {
+		/// Key of the new setting
+		key: String,
+		/// Value of the new setting
+		value: Value,
+	},
+	/// A setting was removed
+	Removed {
+		/// Key of the removed setting
+		key: String,
+		/// Value that the setting had
+		value: Value,
+	},
+	/// A setting was moved to a new key without changing its value
+	Renamed {
+		/// The old key
+		from: String,
+		/// The new key
+		to: String,
+		/// Value of the setting
+		value: Value,
+	},
+	/// The value of a setting was changed
+	Changed {
+		/// Key of the setting
+		key: String,
+		/// The old value
+		old: Value,
+		/// The new value
+		new: Value,
+	},
+}
+
+impl std::fmt::Display for KeyChange {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		use crate::utils::format_short;
+		match self {
+			Self::Added { key, value } => write!(f, "added '{key}': {}", format_short(value)),
+			Self::Removed { key, value } => write!(f, "removed '{key}' (was {})", format_short(value)),
+			Self::Renamed { from, to, value: _ } => write!(f, "renamed '{from}' to '{to}'"),
+			Self::Changed { key, old, new } => write!(f, "changed '{key}' from {} to {}", format_short(old), format_short(new)),
+		}
+	}
+}
+
+
+
+const STASH_PREFIX: &str = " stashed by downgrade from format ";
+
+/// Returns the version and entry text of a stash comment
+fn parse_stash(comment: &str) -> Option<(usize, &str)> {
+	let rest = comment.strip_prefix(STASH_PREFIX)?;
+	let separator_i = rest.find([':', '\n'])?;
+	let version = rest[..separator_i].parse::<usize>().ok()?;
+	let entry = rest[separator_i + 1 ..].trim_start_matches(' ');
+	Some((version, entry))
+}
+
+
+
+/// Lists every change between two versions of a file, where a removed setting and an added setting with the same value are treated as a rename
+pub(crate) fn diff_files(before: &File, after: &File) -> Vec<KeyChange> {
+	let mut removed = before.values.keys().filter(|key| !after.values.contains_key(*key)).collect::<Vec<_>>();
+	let mut added = after.values.keys().filter(|key| !before.values.contains_key(*key)).collect::<Vec<_>>();
+	removed.sort();
+	added.sort();
+	let mut output = vec!();
+	
+	for old_key in removed {
+		let value = &before.values[old_key];
+		let candidates = added.iter().enumerate().filter(|(_i, new_key)| after.values[**new_key] == *value).collect::<Vec<_>>();
+		let renamed_i = match candidates.len() {
+			0 => None,
+			1 => Some(candidates[0].0),
+			_ => {
+				// when there are multiple options, prefer the one that took the old key's place in the layout
+				let old_layout_index = before.layout_index(old_key);
+				candidates.iter().find(|(_i, new_key)| old_layout_index.is_some() && after.layout_index(new_key) == old_layout_index).map(|(i, _new_key)| *i)
+			}
+		};
+		match renamed_i {
+			Some(renamed_i) => {
+				let new_key = added.remove(renamed_i);
+				output.push(KeyChange::Renamed { from: old_key.clone(), to: new_key.clone(), value: value.clone() });
+			}
+			None => output.push(KeyChange::Removed { key: old_key.clone(), value: value.clone() }),
+		}
+	}
+	
+	for new_key in added {
+		output.push(KeyChange::Added { key: new_key.clone(), value: after.values[new_key].clone() });
+	}
+	let mut changed_keys = before.values.keys().filter(|key| after.values.get(*key).is_some_and(|new| *new != before.values[*key])).collect::<Vec<_>>();
+	changed_keys.sort();
+	for key in changed_keys {
+		output.push(KeyChange::Changed { key: key.clone(), old: before.values[key].clone(), new: after.values[key].clone() });
+	}
+	
+	output.sort_by(|a, b| change_key(a).cmp(change_key(b)));
+	output
+}
+
+fn change_key(change: &KeyChange) -> &str {
+	match change {
+		KeyChange::Added { key, .. } | KeyChange::Removed { key, .. } | KeyChange::Changed { key, .. } => key,
+		KeyChange::Renamed { from, .. } => from,
+	}
+}
+
+
+
+impl File {
+	
+	
+	
+	/// Same as `from_str()`, but uses migrations instead of updater functions
+	/// 
+	/// If any migration fails, the returned file is exactly how it was parsed (including its original version) and the error is returned
+	pub fn from_str_with_migrations<T>(contents: impl AsRef<str>, migrations: &[&dyn Migration<T>], args: &mut T) -> (Self, MigrationResult, Vec<ParseEntryError>) {
+		let (mut file, version, mut errors) = Self::parse(contents);
+		if version.is_none() {
+			errors.push(ParseEntryError::new(0, "Could not find version, assuming version is latest"));
+			file.version = migrations.len() + 1;
+		}
+		let result = file.migrate(migrations, args);
+		(file, result, errors)
+	}
+	
+	/// Runs every migration needed to bring this file from `self.version` to the latest version (which is `migrations.len() + 1`), and returns a report of what changed
+	/// 
+	/// If any migration fails, every change is rolled back and the error is returned
+	pub fn migrate<T>(&mut self, migrations: &[&dyn Migration<T>], args: &mut T) -> Result<MigrationReport, MigrationError> {
+		let latest_version = migrations.len() + 1;
+		if self.version == 0 || self.version > latest_version {
+			return Err(MigrationError::new(self.version, latest_version, format!("Version must be between 1 and {latest_version}")));
+		}
+		let original = self.clone();
+		let mut report = MigrationReport {
+			from_version: self.version,
+			to_version: latest_version,
+			steps: vec!(),
+		};
+		for (i, migration) in migrations.iter().enumerate().skip(self.version - 1) {
+			let mut context = MigrationContext {
+				from_version: i + 1,
+				to_version: i + 2,
+				args,
+			};
+			let before = self.clone();
+			if let Err(err) = migration.migrate(self, &mut context) {
+				*self = original;
+				return Err(err);
+			}
+			self.version = i + 2;
+			self.restore_stashed_settings();
+			report.steps.push(MigrationStepReport {
+				from_version: i + 1,
+				to_version: i + 2,
+				changes: diff_files(&before, self),
+			});
+		}
+		Ok(report)
+	}
+	
+	/// Computes the report that `migrate()` would return, without changing this file
+	/// 
+	/// Note: the migrations still receive `args` mutably, so any changes they make to `args` aren't undone
+	pub fn migrate_dry_run<T>(&self, migrations: &[&dyn Migration<T>], args: &mut T) -> Result<MigrationReport, MigrationError> {
+		self.clone().migrate(migrations, args)
+	}
+	
+	/// Runs reverse migrations to bring this file from `self.version` down to `target_version`, and returns a report of what changed
+	/// 
+	/// `downgrades[i]` should undo `migrations[i]` (so it converts from version `i + 2` to version `i + 1`), and any step without a downgrade causes an error. Settings that a step removes are kept as comments, and `migrate()` restores them once the file is upgraded back to the version they were removed from. If any step fails, every change is rolled back and the error is returned
+	/// 
+	/// Note: an older build of a program can't know how to downgrade from newer versions, so this has to be done by the newer build (for example, as part of an uninstall or "export for older version" option)
+	pub fn downgrade<T>(&mut self, target_version: usize, downgrades: &[Option<&dyn Migration<T>>], args: &mut T) -> Result<MigrationReport, MigrationError> {
+		if target_version == 0 || target_version > self.version {
+			return Err(MigrationError::new(self.version, target_version, format!("Target version must be between 1 and {}", self.version)));
+		}
+		let original = self.clone();
+		let mut report = MigrationReport {
+			from_version: self.version,
+			to_version: target_version,
+			steps: vec!(),
+		};
+		for from_version in (target_version + 1 ..= self.version).rev() {
+			let mut context = MigrationContext {
+				from_version,
+				to_version: from_version - 1,
+				args,
+			};
+			let Some(Some(downgrade)) = downgrades.get(from_version - 2) else {
+				*self = original;
+				return Err(context.error("No downgrade is defined for this version"));
+			};
+			let before = self.clone();
+			if let Err(err) = downgrade.migrate(self, &mut context) {
+				*self = original;
+				return Err(err);
+			}
+			self.version = from_version - 1;
+			let changes = diff_files(&before, self);
+			for change in &changes {
+				let KeyChange::Removed { key, value } = change else {continue;};
+				self.stash_setting(from_version, key, value, before.layout_index(key));
+			}
+			report.steps.push(MigrationStepReport {
+				from_version,
+				to_version: from_version - 1,
+				changes,
+			});
+		}
+		Ok(report)
+	}
+	
+	fn stash_setting(&mut self, version: usize, key: &str, value: &Value, old_layout_index: Option<usize>) {
+		let formatted = value.format();
+		let formatted = formatted.trim_end_matches('\n');
+		let comment = if formatted.contains('\n') {
+			LayoutEntry::Comment (format!("{STASH_PREFIX}{version}\n{key}: {formatted}"))
+		} else {
+			LayoutEntry::Comment (format!("{STASH_PREFIX}{version}: {key}: {formatted}"))
+		};
+		// the downgrade might have only removed the value, in which case the key's layout entry is replaced
+		match (self.layout_index(key), old_layout_index) {
+			(Some(layout_index), _) => self.layout[layout_index] = comment,
+			(None, Some(layout_index)) if layout_index <= self.layout.len() => self.layout.insert(layout_index, comment),
+			(None, _) => self.layout.push(comment),
+		}
+	}
+	
+	/// Restores settings that were stashed by `downgrade()` when migrating away from `self.version`
+	fn restore_stashed_settings(&mut self) {
+		let mut layout_i = 0;
+		while layout_i < self.layout.len() {
+			let Some((key, value)) = self.stashed_setting_at(layout_i) else {
+				layout_i += 1;
+				continue;
+			};
+			if self.layout_index(&key).is_some() {
+				self.layout.remove(layout_i);
+			} else {
+				self.layout[layout_i] = LayoutEntry::Key (key.clone());
+				layout_i += 1;
+			}
+			self.values.insert(key, value);
+		}
+	}
+	
+	fn stashed_setting_at(&self, layout_i: usize) -> Option<(String, Value)> {
+		let LayoutEntry::Comment (comment) = &self.layout[layout_i] else {return None;};
+		let (version, entry) = parse_stash(comment)?;
+		if version != self.version {return None;}
+		let (stashed, _version, errors) = Self::parse(format!("format 1\n{entry}"));
+		if !errors.is_empty() {return None;}
+		stashed.values.into_iter().next()
+	}
+	
+	
+	
+}
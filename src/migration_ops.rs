@@ -0,0 +1,244 @@
+use crate::*;
+
+
+
+/// Function type for `MigrationOp::ConvertValue`, which can return an error message
+pub type ConvertFn = fn(&Value) -> Result<Value, String>;
+/// Function type for `MigrationOp::SplitKey`, which can return an error message
+pub type SplitFn = fn(&Value) -> Result<Vec<(String, Value)>, String>;
+/// Function type for `MigrationOp::MergeKeys`, which can return an error message
+pub type MergeFn = fn(&[Option<Value>]) -> Result<Value, String>;
+
+
+
+/// Reusable building block for migrations, so that common changes can be declared instead of written by hand
+/// 
+/// Operations can be used as migrations on their own, or a `Vec` / array of operations can be used as a single migration (where the operations are applied in order). Every operation keeps the layout in sync with the values, and operations that refer to a missing key do nothing
+#[derive(Debug, Clone)]
+pub enum MigrationOp {
+	/// Renames a key, keeping its position in the layout
+	RenameKey {
+		/// The old key
+		from: String,
+		/// The new key
+		to: String,
+	},
+	/// Moves every key in a namespace (and the key that is the namespace's name, if it exists) into a different namespace
+	MoveNamespace {
+		/// The old namespace, without a trailing period
+		from: String,
+		/// The new namespace, without a trailing period
+		to: String,
+	},
+	/// Converts the value of a key, for example from an int in milliseconds to a float in seconds
+	ConvertValue {
+		/// The key to convert
+		key: String,
+		/// Conversion function
+		convert: ConvertFn,
+	},
+	/// Replaces a key with multiple keys, which are put in its place in the layout
+	SplitKey {
+		/// The key to split
+		key: String,
+		/// Function that returns the new key-value pairs
+		split: SplitFn,
+	},
+	/// Replaces multiple keys with a single key, which is put where the first of the keys is in the layout
+	MergeKeys {
+		/// The keys to merge
+		keys: Vec<String>,
+		/// The new key
+		into: String,
+		/// Function that returns the new value (given the value of each key, in the same order as `keys`)
+		merge: MergeFn,
+	},
+	/// Removes a key and leaves a comment in its place, which includes the old value
+	DeleteKey {
+		/// The key to remove
+		key: String,
+		/// Reason for removing the key, which is added to the comment
+		reason: String,
+	},
+	/// Changes the value of a key to a new default, but only if the user hadn't changed it from the old default
+	UpdateDefault {
+		/// The key to update
+		key: String,
+		/// The previous default value
+		old_default: Value,
+		/// The new default value
+		new_default: Value,
+	},
+}
+
+impl MigrationOp {
+	/// Creates a `MigrationOp::RenameKey`
+	pub fn rename_key(from: impl Into<String>, to: impl Into<String>) -> Self {
+		Self::RenameKey { from: from.into(), to: to.into() }
+	}
+	/// Creates a `MigrationOp::MoveNamespace`
+	pub fn move_namespace(from: impl Into<String>, to: impl Into<String>) -> Self {
+		Self::MoveNamespace { from: from.into(), to: to.into() }
+	}
+	/// Creates a `MigrationOp::ConvertValue`
+	pub fn convert_value(key: impl Into<String>, convert: ConvertFn) -> Self {
+		Self::ConvertValue { key: key.into(), convert }
+	}
+	/// Creates a `MigrationOp::SplitKey`
+	pub fn split_key(key: impl Into<String>, split: SplitFn) -> Self {
+		Self::SplitKey { key: key.into(), split }
+	}
+	/// Creates a `MigrationOp::MergeKeys`
+	pub fn merge_keys(keys: impl IntoIterator<Item = impl Into<String>>, into: impl Into<String>, merge: MergeFn) -> Self {
+		Self::MergeKeys { keys: keys.into_iter().map(Into::into).collect(), into: into.into(), merge }
+	}
+	/// Creates a `MigrationOp::DeleteKey`
+	pub fn delete_key(key: impl Into<String>, reason: impl Into<String>) -> Self {
+		Self::DeleteKey { key: key.into(), reason: reason.into() }
+	}
+	/// Creates a `MigrationOp::UpdateDefault`
+	pub fn update_default(key: impl Into<String>, old_default: impl IntoValue, new_default: impl IntoValue) -> Self {
+		Self::UpdateDefault { key: key.into(), old_default: old_default.into_value(), new_default: new_default.into_value() }
+	}
+	
+	/// Applies the operation to a file, returning an error message if it fails
+	pub fn apply(&self, file: &mut File) -> Result<(), String> {
+		match self {
+			
+			Self::RenameKey { from, to } => rename_key(file, from, to),
+			
+			Self::MoveNamespace { from, to } => {
+				let prefix = format!("{from}.");
+				let mut keys = file.values.keys().filter(|key| *key == from || key.starts_with(&prefix)).cloned().collect::<Vec<_>>();
+				keys.sort();
+				for key in keys {
+					let new_key = format!("{to}{}", &key[from.len()..]);
+					rename_key(file, &key, &new_key)?;
+				}
+				Ok(())
+			}
+			
+			Self::ConvertValue { key, convert } => {
+				let Some(value) = file.values.get_mut(key) else {return Ok(());};
+				*value = convert(value).map_err(|err| format!("Could not convert \"{key}\": {err}"))?;
+				Ok(())
+			}
+			
+			Self::SplitKey { key, split } => {
+				let Some(value) = file.values.get(key) else {return Ok(());};
+				let new_values = split(value).map_err(|err| format!("Could not split \"{key}\": {err}"))?;
+				if let Some((new_key, _value)) = new_values.iter().find(|(new_key, _value)| new_key != key && file.values.contains_key(new_key)) {
+					return Err(format!("Could not split \"{key}\" because \"{new_key}\" is already defined"));
+				}
+				file.values.remove(key);
+				let layout_index = file.layout_index(key);
+				if let Some(layout_index) = layout_index {
+					file.layout.remove(layout_index);
+				}
+				for (i, (new_key, new_value)) in new_values.into_iter().enumerate() {
+					if let Some(layout_index) = layout_index {
+						file.layout.insert(layout_index + i, LayoutEntry::Key (new_key.clone()));
+					}
+					file.values.insert(new_key, new_value);
+				}
+				Ok(())
+			}
+			
+			Self::MergeKeys { keys, into, merge } => {
+				if !keys.iter().any(|key| file.values.contains_key(key)) {return Ok(());}
+				if !keys.contains(into) && file.values.contains_key(into) {
+					return Err(format!("Could not merge into \"{into}\" because it is already defined"));
+				}
+				let old_values = keys.iter().map(|key| file.values.get(key).cloned()).collect::<Vec<_>>();
+				let new_value = merge(&old_values).map_err(|err| format!("Could not merge into \"{into}\": {err}"))?;
+				let layout_index = keys.iter().filter_map(|key| file.layout_index(key)).min();
+				if let Some(layout_index) = layout_index {
+					file.layout[layout_index] = LayoutEntry::Key (into.clone());
+				}
+				// the merged key can be one of the keys, so only the entry that was just written is kept
+				let mut i = 0;
+				file.layout.retain(|entry| {
+					let keep = Some(i) == layout_index || !matches!(entry, LayoutEntry::Key (key) if keys.contains(key) || key == into);
+					i += 1;
+					keep
+				});
+				for key in keys {
+					file.values.remove(key);
+				}
+				file.values.insert(into.clone(), new_value);
+				Ok(())
+			}
+			
+			Self::DeleteKey { key, reason } => {
+				let Some(value) = file.values.remove(key) else {return Ok(());};
+				let formatted = value.format();
+				let formatted = formatted.trim_end_matches('\n');
+				let tombstone = if formatted.contains('\n') {
+					LayoutEntry::Comment (format!("removed setting ({reason}):\n{key}: {formatted}"))
+				} else {
+					LayoutEntry::Comment (format!(" removed setting ({reason}): {key}: {formatted}"))
+				};
+				match file.layout_index(key) {
+					Some(layout_index) => file.layout[layout_index] = tombstone,
+					None => file.layout.push(tombstone),
+				}
+				Ok(())
+			}
+			
+			Self::UpdateDefault { key, old_default, new_default } => {
+				let Some(value) = file.values.get_mut(key) else {return Ok(());};
+				if value == old_default {
+					*value = new_default.clone();
+				}
+				Ok(())
+			}
+			
+		}
+	}
+	
+}
+
+
+
+fn rename_key(file: &mut File, from: &str, to: &str) -> Result<(), String> {
+	if from == to {return Ok(());}
+	if file.values.contains_key(to) {
+		if file.values.contains_key(from) {return Err(format!("Could not rename \"{from}\" to \"{to}\" because \"{to}\" is already defined"));}
+		return Ok(());
+	}
+	let Some(value) = file.values.remove(from) else {return Ok(());};
+	file.values.insert(to.to_string(), value);
+	if let Some(layout_index) = file.layout_index(from) {
+		file.layout[layout_index] = LayoutEntry::Key (to.to_string());
+	}
+	Ok(())
+}
+
+
+
+impl<T> Migration<T> for MigrationOp {
+	fn migrate(&self, file: &mut File, context: &mut MigrationContext<T>) -> Result<(), MigrationError> {
+		self.apply(file).map_err(|err| context.error(err))
+	}
+}
+
+impl<T> Migration<T> for [MigrationOp] {
+	fn migrate(&self, file: &mut File, context: &mut MigrationContext<T>) -> Result<(), MigrationError> {
+		for op in self {
+			op.apply(file).map_err(|err| context.error(err))?;
+		}
+		Ok(())
+	}
+}
+
+impl<T> Migration<T> for Vec<MigrationOp> {
+	fn migrate(&self, file: &mut File, context: &mut MigrationContext<T>) -> Result<(), MigrationError> {
+		self.as_slice().migrate(file, context)
+	}
+}
+
+impl<T, const N: usize> Migration<T> for [MigrationOp; N] {
+	fn migrate(&self, file: &mut File, context: &mut MigrationContext<T>) -> Result<(), MigrationError> {
+		self.as_slice().migrate(file, context)
+	}
+}
@@ -0,0 +1,173 @@
+use crate::*;
+
+
+
+/// Name of the reserved key that holds a namespace's version (so the version of namespace `plugin` is stored at `plugin.format`)
+pub const NAMESPACE_VERSION_KEY: &str = "format";
+
+
+
+/// A namespace that has its own version and migrations, separate from the version of the file that contains it
+/// 
+/// This is meant for things like plugins, which own a namespace within a shared settings file and need to update their settings without the main program knowing about their migrations
+pub struct VersionedNamespace<'a, T> {
+	/// Name of the namespace, without the trailing period
+	pub namespace: String,
+	/// Migrations for the namespace's settings (the latest version is `migrations.len() + 1`)
+	pub migrations: &'a [&'a dyn Migration<T>],
+}
+
+impl<'a, T> VersionedNamespace<'a, T> {
+	/// Basic constructor
+	pub fn new(namespace: impl Into<String>, migrations: &'a [&'a dyn Migration<T>]) -> Self {
+		Self {
+			namespace: namespace.into(),
+			migrations,
+		}
+	}
+	/// Returns the latest version of the namespace
+	pub fn latest_version(&self) -> usize {
+		self.migrations.len() + 1
+	}
+}
+
+
+
+impl File {
+	
+	
+	
+	/// Returns the version of a namespace, which is stored in the reserved key `{namespace}.format`
+	/// 
+	/// Returns `None` if the version is missing or isn't a positive integer
+	pub fn namespace_version(&self, namespace: &str) -> Option<usize> {
+		match self.values.get(&namespace_version_key(namespace)) {
+			Some(Value::I64 (version)) if *version > 0 => usize::try_from(*version).ok(),
+			_ => None,
+		}
+	}
+	
+	/// Sets the version of a namespace, adding the `{namespace}.format` key to the layout if needed
+	/// 
+	/// The key is placed directly above the namespace's first setting, or at the end of the file if the namespace has no settings
+	pub fn set_namespace_version(&mut self, namespace: &str, version: usize) {
+		let version_key = namespace_version_key(namespace);
+		self.values.insert(version_key.clone(), Value::I64 (version as i64));
+		if self.layout_index(&version_key).is_some() {return;}
+		match self.namespace_layout_range(namespace) {
+			Some((start, _end)) => self.layout.insert(start, LayoutEntry::Key (version_key)),
+			None => self.layout.push(LayoutEntry::Key (version_key)),
+		}
+	}
+	
+	/// Returns a new file that only holds the settings in a namespace, with the namespace's name removed from each key
+	/// 
+	/// The new file's version is the namespace's version (or 0 if it doesn't have one), and its layout holds everything between the namespace's first and last settings (except for settings outside the namespace). This is mainly useful for running a namespace's migrations, which is done by `migrate_namespace()`
+	pub fn extract_namespace(&self, namespace: &str) -> File {
+		let prefix = format!("{namespace}.");
+		let version_key = namespace_version_key(namespace);
+		let mut output = File::new(self.namespace_version(namespace).unwrap_or(0));
+		for (key, value) in &self.values {
+			if *key == version_key {continue;}
+			let Some(inner_key) = key.strip_prefix(&prefix) else {continue;};
+			output.values.insert(inner_key.to_string(), value.clone());
+		}
+		if let Some((start, end)) = self.namespace_layout_range(namespace) {
+			for entry in &self.layout[start..=end] {
+				match entry {
+					LayoutEntry::Key (key) if *key == version_key => {}
+					LayoutEntry::Key (key) => {
+						let Some(inner_key) = key.strip_prefix(&prefix) else {continue;};
+						output.layout.push(LayoutEntry::Key (inner_key.to_string()));
+					}
+					_ => output.layout.push(entry.clone()),
+				}
+			}
+		}
+		output
+	}
+	
+	/// Replaces every setting in a namespace with the settings of `contents` (which is usually a file returned by `extract_namespace()`), and sets the namespace's version to `contents.version`
+	/// 
+	/// The new layout takes the place of the namespace's old layout, and any settings from outside the namespace that were mixed in with it are moved to directly after it
+	pub fn replace_namespace(&mut self, namespace: &str, contents: &File) {
+		let prefix = format!("{namespace}.");
+		let version_key = namespace_version_key(namespace);
+		self.values.retain(|key, _value| *key == version_key || !key.starts_with(&prefix));
+		for (inner_key, value) in &contents.values {
+			self.values.insert(format!("{prefix}{inner_key}"), value.clone());
+		}
+		
+		let mut new_layout = vec!();
+		let insert_index = match self.namespace_layout_range(namespace) {
+			Some((start, end)) => {
+				let old_entries = self.layout.drain(start..=end).collect::<Vec<_>>();
+				if old_entries.contains(&LayoutEntry::Key (version_key.clone())) {
+					new_layout.push(LayoutEntry::Key (version_key.clone()));
+				}
+				let foreign_keys = old_entries.into_iter().filter(|entry| matches!(entry, LayoutEntry::Key (key) if !key.starts_with(&prefix)));
+				new_layout.extend(contents.layout.iter().map(|entry| prefix_layout_entry(entry, &prefix)));
+				new_layout.extend(foreign_keys);
+				start
+			}
+			None => {
+				new_layout.extend(contents.layout.iter().map(|entry| prefix_layout_entry(entry, &prefix)));
+				self.layout.len()
+			}
+		};
+		self.layout.splice(insert_index..insert_index, new_layout);
+		self.set_namespace_version(namespace, contents.version);
+	}
+	
+	/// Runs every migration needed to bring a namespace up to its latest version, without touching any settings outside of the namespace
+	/// 
+	/// The migrations are given a file that only holds the namespace's settings (see `extract_namespace()`), so they don't need to know the namespace's name. If the namespace doesn't have a version yet, it is assumed to be at version 1 if it has any settings (since they were written before the namespace was versioned), or at the latest version if it doesn't. Either way, the version is stored in `{namespace}.format` afterwards. If any migration fails, every change is rolled back and the error is returned
+	pub fn migrate_namespace<T>(&mut self, namespace: &VersionedNamespace<T>, args: &mut T) -> Result<MigrationReport, MigrationError> {
+		let mut contents = self.extract_namespace(&namespace.namespace);
+		if self.namespace_version(&namespace.namespace).is_none() {
+			if self.values.contains_key(&namespace_version_key(&namespace.namespace)) {
+				let message = format!("The version of namespace \"{}\" must be a positive integer", namespace.namespace);
+				return Err(MigrationError::new(0, namespace.latest_version(), message));
+			}
+			contents.version = if contents.values.is_empty() {namespace.latest_version()} else {1};
+		}
+		let report = contents.migrate(namespace.migrations, args).map_err(|err| {
+			MigrationError::new(err.from_version, err.to_version, format!("(namespace \"{}\") {}", namespace.namespace, err.message))
+		})?;
+		self.replace_namespace(&namespace.namespace, &contents);
+		Ok(report)
+	}
+	
+	/// Same as `migrate_namespace()`, but for several namespaces at once (for example, one for each plugin)
+	/// 
+	/// Each namespace is migrated independently, so one namespace failing doesn't stop the others from being migrated
+	pub fn migrate_namespaces<T>(&mut self, namespaces: &[VersionedNamespace<T>], args: &mut T) -> Vec<Result<MigrationReport, MigrationError>> {
+		namespaces.iter().map(|namespace| self.migrate_namespace(namespace, args)).collect()
+	}
+	
+	/// Returns the first and last layout indices of a namespace's settings
+	fn namespace_layout_range(&self, namespace: &str) -> Option<(usize, usize)> {
+		let prefix = format!("{namespace}.");
+		let mut indices = self.layout.iter().enumerate()
+			.filter(|(_i, entry)| matches!(entry, LayoutEntry::Key (key) if key.starts_with(&prefix)))
+			.map(|(i, _entry)| i);
+		let start = indices.next()?;
+		Some((start, indices.next_back().unwrap_or(start)))
+	}
+	
+	
+	
+}
+
+
+
+fn namespace_version_key(namespace: &str) -> String {
+	format!("{namespace}.{NAMESPACE_VERSION_KEY}")
+}
+
+fn prefix_layout_entry(entry: &LayoutEntry, prefix: &str) -> LayoutEntry {
+	match entry {
+		LayoutEntry::Key (key) => LayoutEntry::Key (format!("{prefix}{key}")),
+		_ => entry.clone(),
+	}
+}
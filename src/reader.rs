@@ -0,0 +1,103 @@
+use crate::*;
+
+
+
+/// Wrapper around a file that returns defaults for invalid settings instead of errors, so every error can be reported at once
+/// 
+/// Once every setting is read, call `finish()` to get the list of errors
+#[derive(Debug, Clone)]
+pub struct SettingsReader<'a> {
+	/// The file being read
+	pub file: &'a File,
+	errors: Vec<SettingError>,
+}
+
+impl<'a> SettingsReader<'a> {
+	
+	/// Creates a new reader with no errors
+	pub fn new(file: &'a File) -> Self {
+		Self {
+			file,
+			errors: vec!(),
+		}
+	}
+	
+	/// Returns the list of every error that was encountered
+	pub fn finish(self) -> Vec<SettingError> {
+		self.errors
+	}
+	
+	/// Returns the errors that have been encountered so far
+	pub fn errors(&self) -> &[SettingError] {
+		&self.errors
+	}
+	
+	fn unwrap_or_record<T>(&mut self, key: &str, result: Result<T, RetrieveSettingError>, default: T) -> T {
+		match result {
+			Ok(value) => value,
+			Err(error) => {
+				self.errors.push(SettingError { line: self.file.line_of(key), error });
+				default
+			}
+		}
+	}
+	
+	/// Same as `File::get()`, but records the error and returns `default` if the setting is missing or invalid
+	pub fn get<T: FromValue>(&mut self, key: impl AsRef<str>, default: T) -> T {
+		let key = key.as_ref();
+		let result = self.file.get(key);
+		self.unwrap_or_record(key, result, default)
+	}
+	
+	/// Same as `File::get_enum()`, but records the error and returns `default` if the setting is missing or invalid
+	pub fn get_enum<T: EcfEnum>(&mut self, key: impl AsRef<str>, default: T) -> T {
+		let key = key.as_ref();
+		let result = self.file.get_enum(key);
+		self.unwrap_or_record(key, result, default)
+	}
+	
+	/// Same as `File::get_int()`, but records the error and returns `default` if the setting is missing or invalid
+	pub fn get_int(&mut self, key: impl AsRef<str>, default: i64) -> i64 {
+		let key = key.as_ref();
+		let result = self.file.get_int(key);
+		self.unwrap_or_record(key, result, default)
+	}
+	
+	/// Same as `File::get_float()`, but records the error and returns `default` if the setting is missing or invalid
+	pub fn get_float(&mut self, key: impl AsRef<str>, default: f64) -> f64 {
+		let key = key.as_ref();
+		let result = self.file.get_float(key);
+		self.unwrap_or_record(key, result, default)
+	}
+	
+	/// Same as `File::get_number()`, but records the error and returns `default` if the setting is missing or invalid
+	pub fn get_number(&mut self, key: impl AsRef<str>, default: f64) -> f64 {
+		let key = key.as_ref();
+		let result = self.file.get_number(key);
+		self.unwrap_or_record(key, result, default)
+	}
+	
+	/// Same as `File::get_bool()`, but records the error and returns `default` if the setting is missing or invalid
+	pub fn get_bool(&mut self, key: impl AsRef<str>, default: bool) -> bool {
+		let key = key.as_ref();
+		let result = self.file.get_bool(key);
+		self.unwrap_or_record(key, result, default)
+	}
+	
+	/// Same as `File::get_str()`, but records the error and returns `default` if the setting is missing or invalid
+	pub fn get_str(&mut self, key: impl AsRef<str>, default: &'a str) -> &'a str {
+		let key = key.as_ref();
+		let result = self.file.get_str(key);
+		self.unwrap_or_record(key, result, default)
+	}
+	
+}
+
+
+
+impl File {
+	/// Creates a `SettingsReader` for this file
+	pub fn reader(&self) -> SettingsReader<'_> {
+		SettingsReader::new(self)
+	}
+}
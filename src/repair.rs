@@ -0,0 +1,81 @@
+use crate::*;
+
+
+
+/// Record of a setting being reset to its default value by `File::get_or_repair()`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repair {
+	/// The key that was repaired
+	pub key: String,
+	/// The invalid value that was replaced (this is `None` if the setting was missing)
+	pub original: Option<Value>,
+	/// The default value that was inserted
+	pub replacement: Value,
+	/// Why the original value couldn't be used
+	pub reason: RetrieveSettingError,
+}
+
+impl std::fmt::Display for Repair {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match &self.original {
+			Some(original) => write!(f, "Setting '{}' was reset from {} to {} ({})", self.key, crate::utils::format_short(original), crate::utils::format_short(&self.replacement), self.reason),
+			None => write!(f, "Setting '{}' was missing and has been set to {}", self.key, crate::utils::format_short(&self.replacement)),
+		}
+	}
+}
+
+
+
+impl File {
+	
+	/// Gets the value of a setting, or if the setting is missing or invalid, sets it to `default` and returns `default` along with a record of the repair
+	/// 
+	/// Invalid values are kept as a comment above the setting so that nothing the user typed is lost. The repairs from several calls can be collected into a list (for example with `repairs.extend(repair)`) so they can all be shown to the user at once
+	pub fn get_or_repair<T: FromValue + IntoValue + Clone>(&mut self, key: impl AsRef<str>, default: T) -> (T, Option<Repair>) {
+		let key = self.resolve_key(key.as_ref()).to_string();
+		let reason = match self.get::<T>(&key) {
+			Ok(value) => return (value, None),
+			Err(err) => err,
+		};
+		let replacement = default.clone().into_value();
+		let original = self.values.insert(key.clone(), replacement.clone());
+		match &original {
+			Some(original) => self.insert_comment_above(&key, invalid_value_comment(&key, original)),
+			None if self.layout_index(&key).is_none() => self.layout.push(LayoutEntry::Key (key.clone())),
+			None => {}
+		}
+		let repair = Repair {
+			key,
+			original,
+			replacement,
+			reason,
+		};
+		(default, Some(repair))
+	}
+	
+	/// Inserts a comment directly above a key in the layout, or at the end of the layout (followed by the key) if the key isn't in the layout
+	pub fn insert_comment_above(&mut self, key: impl AsRef<str>, comment: impl Into<String>) {
+		let key = key.as_ref();
+		let comment = LayoutEntry::Comment (comment.into());
+		match self.layout_index(key) {
+			Some(index) => self.layout.insert(index, comment),
+			None => {
+				self.layout.push(comment);
+				self.layout.push(LayoutEntry::Key (key.to_string()));
+			}
+		}
+	}
+	
+}
+
+
+
+fn invalid_value_comment(key: &str, original: &Value) -> String {
+	let formatted = original.format();
+	let formatted = formatted.trim_end_matches('\n');
+	if formatted.contains('\n') {
+		format!("invalid value (reset to default):\n{key}: {formatted}")
+	} else {
+		format!(" invalid value (reset to default): {key}: {formatted}")
+	}
+}
@@ -0,0 +1,468 @@
+use crate::*;
+
+
+
+/// Type of value that a schema entry expects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ValueType {
+	/// Any type of value
+	Any,
+	/// Only `Value::Empty`
+	Empty,
+	/// Only `Value::I64`
+	Int,
+	/// Only `Value::F64`
+	Float,
+	/// Either `Value::I64` or `Value::F64`
+	Number,
+	/// Only `Value::Bool`
+	Bool,
+	/// Only `Value::String`
+	String,
+}
+
+impl ValueType {
+	/// Returns "any", "empty", "int", "float", "number", "bool", or "string" according to enum state
+	pub const fn as_str(&self) -> &'static str {
+		match self {
+			Self::Any => "any",
+			Self::Empty => "empty",
+			Self::Int => "int",
+			Self::Float => "float",
+			Self::Number => "number",
+			Self::Bool => "bool",
+			Self::String => "string",
+		}
+	}
+	/// Opposite of `as_str()` (non-case-dependant)
+	pub fn from_name(name: &str) -> Option<Self> {
+		match &*name.to_lowercase() {
+			"any" => Some(Self::Any),
+			"empty" => Some(Self::Empty),
+			"int" => Some(Self::Int),
+			"float" => Some(Self::Float),
+			"number" => Some(Self::Number),
+			"bool" => Some(Self::Bool),
+			"string" => Some(Self::String),
+			_ => None,
+		}
+	}
+	/// Returns whether a value is of this type
+	pub const fn matches(&self, value: &Value) -> bool {
+		matches!(
+			(self, value),
+			(Self::Any, _)
+			| (Self::Empty, Value::Empty)
+			| (Self::Int, Value::I64 (_))
+			| (Self::Float, Value::F64 (_))
+			| (Self::Number, Value::I64 (_) | Value::F64 (_))
+			| (Self::Bool, Value::Bool (_))
+			| (Self::String, Value::String (_))
+		)
+	}
+}
+
+
+
+/// Describes a single expected setting
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaEntry {
+	/// Key of the setting
+	pub key: String,
+	/// Expected type of the setting
+	pub value_type: ValueType,
+	/// Whether the setting has to exist (optional settings are also allowed to be `empty`)
+	pub required: bool,
+	/// Value used by `Schema::apply_defaults()`
+	pub default: Option<Value>,
+	/// Smallest allowed value (only checked for ints and floats)
+	pub min: Option<f64>,
+	/// Largest allowed value (only checked for ints and floats)
+	pub max: Option<f64>,
+	/// Pattern that string values have to match, where `*` matches any number of chars and `?` matches exactly one char
+	pub pattern: Option<String>,
+	/// List of allowed values, or no restriction if this is empty
+	pub allowed: Vec<Value>,
+	/// Description of the setting, which is added as a comment by `Schema::apply_defaults()`
+	pub description: Option<String>,
+}
+
+impl SchemaEntry {
+	/// Creates an optional entry with no restrictions other than its type
+	pub fn new(key: impl Into<String>, value_type: ValueType) -> Self {
+		Self {
+			key: key.into(),
+			value_type,
+			required: false,
+			default: None,
+			min: None,
+			max: None,
+			pattern: None,
+			allowed: vec!(),
+			description: None,
+		}
+	}
+	/// Marks the setting as required
+	pub fn required(mut self) -> Self {
+		self.required = true;
+		self
+	}
+	/// Sets the default value
+	pub fn default(mut self, default: impl IntoValue) -> Self {
+		self.default = Some(default.into_value());
+		self
+	}
+	/// Sets the smallest and largest allowed values (both inclusive)
+	pub fn range(mut self, min: f64, max: f64) -> Self {
+		self.min = Some(min);
+		self.max = Some(max);
+		self
+	}
+	/// Sets the smallest allowed value (inclusive)
+	pub fn min(mut self, min: f64) -> Self {
+		self.min = Some(min);
+		self
+	}
+	/// Sets the largest allowed value (inclusive)
+	pub fn max(mut self, max: f64) -> Self {
+		self.max = Some(max);
+		self
+	}
+	/// Sets the pattern that string values have to match
+	pub fn pattern(mut self, pattern: impl Into<String>) -> Self {
+		self.pattern = Some(pattern.into());
+		self
+	}
+	/// Sets the list of allowed values
+	pub fn allowed(mut self, allowed: impl IntoIterator<Item = impl IntoValue>) -> Self {
+		self.allowed = allowed.into_iter().map(IntoValue::into_value).collect();
+		self
+	}
+	/// Sets the description
+	pub fn description(mut self, description: impl Into<String>) -> Self {
+		self.description = Some(description.into());
+		self
+	}
+	/// Describes the entry's restrictions in plain text, with one line for each type of restriction
+	pub fn restrictions(&self) -> Vec<String> {
+		let mut output = vec!();
+		match (self.min, self.max) {
+			(Some(min), Some(max)) => output.push(format!("allowed range: {} to {}", number_value(min).format(), number_value(max).format())),
+			(Some(min), None) => output.push(format!("minimum: {}", number_value(min).format())),
+			(None, Some(max)) => output.push(format!("maximum: {}", number_value(max).format())),
+			(None, None) => {}
+		}
+		if let Some(pattern) = &self.pattern {
+			output.push(format!("must match: \"{pattern}\""));
+		}
+		if !self.allowed.is_empty() {
+			let allowed = self.allowed.iter().map(Value::format).collect::<Vec<_>>();
+			output.push(format!("allowed values: {}", allowed.join(", ")));
+		}
+		output
+	}
+	/// Checks a single value against this entry, returning the first problem found
+	pub fn check(&self, value: &Value) -> Option<ViolationKind> {
+		if matches!(value, Value::Empty) && !self.required {return None;}
+		if !self.value_type.matches(value) {
+			return Some(ViolationKind::WrongType { expected: self.value_type, encountered: value.type_as_string() });
+		}
+		let number = match value {
+			Value::I64 (v) => Some(*v as f64),
+			Value::F64 (v) => Some(*v),
+			_ => None,
+		};
+		if let Some(number) = number {
+			if let Some(min) = self.min && number < min {return Some(ViolationKind::BelowMinimum { min });}
+			if let Some(max) = self.max && number > max {return Some(ViolationKind::AboveMaximum { max });}
+		}
+		if let (Some(pattern), Value::String (string)) = (&self.pattern, value) && !crate::utils::glob_match(pattern, string) {
+			return Some(ViolationKind::PatternMismatch { pattern: pattern.clone() });
+		}
+		if !self.allowed.is_empty() && !self.allowed.contains(value) {
+			return Some(ViolationKind::NotAllowed { allowed: self.allowed.clone() });
+		}
+		None
+	}
+}
+
+
+
+/// Describes every expected setting of a file
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Schema {
+	/// Entries in the order that they should appear in a file
+	pub entries: Vec<SchemaEntry>,
+}
+
+impl Schema {
+	
+	/// Creates an empty schema
+	pub fn new() -> Self {
+		Self::default()
+	}
+	
+	/// Adds an entry to the schema
+	pub fn entry(mut self, entry: SchemaEntry) -> Self {
+		self.entries.push(entry);
+		self
+	}
+	
+	/// Returns the entry for a key
+	pub fn get(&self, key: impl AsRef<str>) -> Option<&SchemaEntry> {
+		let key = key.as_ref();
+		self.entries.iter().find(|entry| entry.key == key)
+	}
+	
+	/// Checks every setting that the schema describes, and returns every problem found
+	pub fn validate(&self, file: &File) -> Vec<SchemaViolation> {
+		let mut output = vec!();
+		for entry in &self.entries {
+			let kind = match file.values.get(&entry.key) {
+				None if entry.required => ViolationKind::Missing,
+				None => continue,
+				Some(value) => {
+					let Some(kind) = entry.check(value) else {continue;};
+					kind
+				}
+			};
+			output.push(SchemaViolation {
+				key: entry.key.clone(),
+				line: file.line_of(&entry.key),
+				value: file.values.get(&entry.key).cloned(),
+				kind,
+			});
+		}
+		output
+	}
+	
+	/// Adds every missing setting that has a default, along with its description as a comment, and returns the keys that were added
+	pub fn apply_defaults(&self, file: &mut File) -> Vec<String> {
+		let mut output = vec!();
+		for entry in &self.entries {
+			let Some(default) = &entry.default else {continue;};
+			if file.values.contains_key(&entry.key) {continue;}
+			file.values.insert(entry.key.clone(), default.clone());
+			if file.layout_index(&entry.key).is_none() {
+				if let Some(description) = &entry.description {
+					file.layout.push(description_comment(description));
+				}
+				file.layout.push(LayoutEntry::Key (entry.key.clone()));
+			}
+			output.push(entry.key.clone());
+		}
+		output
+	}
+	
+	
+	
+	/// Creates a complete settings file with every setting set to its default (or `empty` if there is no default)
+	/// 
+	/// Required settings that have no default and can't be `empty` are written as a commented-out placeholder instead, since there is no valid value to give them (so `validate()` reports them as missing until the user fills them in). Settings are grouped by namespace (in the order that each namespace first appears in the schema) and the groups are separated by empty lines. Each setting is preceded by its description and its restrictions as comments
+	pub fn generate_file(&self, version: usize) -> File {
+		let mut namespaces: Vec<(&str, Vec<&SchemaEntry>)> = vec!();
+		for entry in &self.entries {
+			let namespace = entry.key.rsplit_once('.').map(|(namespace, _name)| namespace).unwrap_or("");
+			match namespaces.iter_mut().find(|(other, _entries)| *other == namespace) {
+				Some((_namespace, entries)) => entries.push(entry),
+				None => namespaces.push((namespace, vec!(entry))),
+			}
+		}
+		
+		let mut file = File::new(version);
+		for (i, (_namespace, entries)) in namespaces.into_iter().enumerate() {
+			if i > 0 {file.layout.push(LayoutEntry::Empty);}
+			for entry in entries {
+				if let Some(description) = &entry.description {
+					file.layout.push(description_comment(description));
+				}
+				for restriction in entry.restrictions() {
+					file.layout.push(LayoutEntry::Comment (format!(" {restriction}")));
+				}
+				let value = entry.default.clone().unwrap_or(Value::Empty);
+				// a required setting without a default has no valid value to write, so the user has to fill it in
+				if entry.default.is_none() && entry.required && entry.check(&value).is_some() {
+					file.layout.push(LayoutEntry::Comment (format!(" {}: (required {}, please set a value and remove the '#')", entry.key, entry.value_type.as_str())));
+					continue;
+				}
+				file.layout.push(LayoutEntry::Key (entry.key.clone()));
+				file.values.insert(entry.key.clone(), value);
+			}
+		}
+		file
+	}
+	
+	
+	
+	/// Reads a schema that is written as an ecf file, where each attribute of a setting is a key within the setting's namespace:
+	/// 
+	/// ```txt
+	/// format 1
+	/// volume.type: "int"
+	/// volume.required: true
+	/// volume.default: 50
+	/// volume.min: 0
+	/// volume.max: 100
+	/// volume.description: "Master volume, in percent"
+	/// 
+	/// log level.type: "string"
+	/// log level.allowed.0: "debug"
+	/// log level.allowed.1: "info"
+	/// log level.pattern: "*"
+	/// ```
+	/// 
+	/// Entries are ordered by where they first appear in the file, and invalid attributes are skipped and returned as errors
+	pub fn from_file(file: &File) -> (Self, Vec<SchemaParseError>) {
+		let mut entries: Vec<SchemaEntry> = vec!();
+		let mut allowed_values: Vec<Vec<(usize, Value)>> = vec!();
+		let mut errors = vec!();
+		
+		let mut keys = file.layout.iter().filter_map(|entry| match entry {
+			LayoutEntry::Key (key) if file.values.contains_key(key) => Some(key.as_str()),
+			_ => None,
+		}).collect::<Vec<_>>();
+		let mut unordered_keys = file.values.keys().map(String::as_str).filter(|key| !keys.contains(key)).collect::<Vec<_>>();
+		unordered_keys.sort();
+		keys.append(&mut unordered_keys);
+		
+		for full_key in keys {
+			let value = &file.values[full_key];
+			let new_error = |message: String| SchemaParseError { line: file.line_of(full_key), message };
+			let Some((key, attribute)) = split_schema_key(full_key) else {
+				errors.push(new_error(format!("Key \"{full_key}\" does not have an attribute, it should look like \"{full_key}.type\".")));
+				continue;
+			};
+			let entry_i = match entries.iter().position(|entry| entry.key == key) {
+				Some(entry_i) => entry_i,
+				None => {
+					entries.push(SchemaEntry::new(key, ValueType::Any));
+					allowed_values.push(vec!());
+					entries.len() - 1
+				}
+			};
+			let entry = &mut entries[entry_i];
+			match (attribute, value) {
+				(SchemaAttribute::Type, Value::String (name)) => match ValueType::from_name(name) {
+					Some(value_type) => entry.value_type = value_type,
+					None => errors.push(new_error(format!("Unknown type \"{name}\", must be \"any\", \"empty\", \"int\", \"float\", \"number\", \"bool\", or \"string\"."))),
+				},
+				(SchemaAttribute::Required, Value::Bool (required)) => entry.required = *required,
+				(SchemaAttribute::Default, _) => entry.default = Some(value.clone()),
+				(SchemaAttribute::Min, Value::I64 (min)) => entry.min = Some(*min as f64),
+				(SchemaAttribute::Min, Value::F64 (min)) => entry.min = Some(*min),
+				(SchemaAttribute::Max, Value::I64 (max)) => entry.max = Some(*max as f64),
+				(SchemaAttribute::Max, Value::F64 (max)) => entry.max = Some(*max),
+				(SchemaAttribute::Pattern, Value::String (pattern)) => entry.pattern = Some(pattern.clone()),
+				(SchemaAttribute::Allowed (index), _) => allowed_values[entry_i].push((index, value.clone())),
+				(SchemaAttribute::Description, Value::String (description)) => entry.description = Some(description.clone()),
+				(SchemaAttribute::Unknown (attribute), _) => errors.push(new_error(format!("Unknown attribute \"{attribute}\", must be \"type\", \"required\", \"default\", \"min\", \"max\", \"pattern\", \"allowed.N\", or \"description\"."))),
+				(attribute, _) => errors.push(new_error(format!("Attribute \"{}\" cannot be {}, it must be {}.", attribute.name(), value.type_as_singular_string(), attribute.expected_type()))),
+			}
+		}
+		
+		for (entry, mut allowed) in entries.iter_mut().zip(allowed_values) {
+			allowed.sort_by_key(|(index, _value)| *index);
+			entry.allowed = allowed.into_iter().map(|(_index, value)| value).collect();
+		}
+		(Self { entries }, errors)
+	}
+	
+	/// Writes the schema as an ecf file, opposite of `from_file()`
+	pub fn to_file(&self) -> File {
+		let mut file = File::new(1);
+		for (i, entry) in self.entries.iter().enumerate() {
+			if i > 0 {file.layout.push(LayoutEntry::Empty);}
+			let mut push = |attribute: &str, value: Value| {
+				let full_key = format!("{}.{attribute}", entry.key);
+				file.layout.push(LayoutEntry::Key (full_key.clone()));
+				file.values.insert(full_key, value);
+			};
+			push("type", Value::String (entry.value_type.as_str().to_string()));
+			if entry.required {push("required", Value::Bool (true));}
+			if let Some(default) = &entry.default {push("default", default.clone());}
+			if let Some(min) = entry.min {push("min", number_value(min));}
+			if let Some(max) = entry.max {push("max", number_value(max));}
+			if let Some(pattern) = &entry.pattern {push("pattern", Value::String (pattern.clone()));}
+			for (i, allowed) in entry.allowed.iter().enumerate() {
+				push(&format!("allowed.{i}"), allowed.clone());
+			}
+			if let Some(description) = &entry.description {push("description", Value::String (description.clone()));}
+		}
+		file
+	}
+	
+}
+
+
+
+enum SchemaAttribute<'a> {
+	Type,
+	Required,
+	Default,
+	Min,
+	Max,
+	Pattern,
+	Allowed (usize),
+	Description,
+	Unknown (&'a str),
+}
+
+impl SchemaAttribute<'_> {
+	fn name(&self) -> &str {
+		match self {
+			Self::Type => "type",
+			Self::Required => "required",
+			Self::Default => "default",
+			Self::Min => "min",
+			Self::Max => "max",
+			Self::Pattern => "pattern",
+			Self::Allowed (_) => "allowed",
+			Self::Description => "description",
+			Self::Unknown (attribute) => attribute,
+		}
+	}
+	fn expected_type(&self) -> &'static str {
+		match self {
+			Self::Required => "a Bool",
+			Self::Min | Self::Max => "an Int or a Float",
+			_ => "a String",
+		}
+	}
+}
+
+fn split_schema_key(full_key: &str) -> Option<(&str, SchemaAttribute<'_>)> {
+	let (rest, last) = full_key.rsplit_once('.')?;
+	if let Ok(index) = last.parse::<usize>() && let Some(key) = rest.strip_suffix(".allowed") {
+		return Some((key, SchemaAttribute::Allowed (index)));
+	}
+	let attribute = match last {
+		"type" => SchemaAttribute::Type,
+		"required" => SchemaAttribute::Required,
+		"default" => SchemaAttribute::Default,
+		"min" => SchemaAttribute::Min,
+		"max" => SchemaAttribute::Max,
+		"pattern" => SchemaAttribute::Pattern,
+		"description" => SchemaAttribute::Description,
+		_ => SchemaAttribute::Unknown (last),
+	};
+	Some((rest, attribute))
+}
+
+/// Whole numbers are written as ints so that schema files stay readable
+fn number_value(number: f64) -> Value {
+	if number.fract() == 0.0 && number.abs() < i64::MAX as f64 {
+		Value::I64 (number as i64)
+	} else {
+		Value::F64 (number)
+	}
+}
+
+
+
+pub(crate) fn description_comment(description: &str) -> LayoutEntry {
+	if description.contains('\n') {
+		LayoutEntry::Comment (description.to_string())
+	} else {
+		LayoutEntry::Comment (format!(" {description}"))
+	}
+}
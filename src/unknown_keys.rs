@@ -0,0 +1,95 @@
+use crate::*;
+
+
+
+/// Setting that isn't in the list of known keys, found by `File::find_unknown_keys()`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UnknownKey {
+	/// The unknown key
+	pub key: String,
+	/// Line number of the setting (using 1-based indexing), or `None` if the setting isn't in the file's layout
+	pub line: Option<usize>,
+	/// The known key that is closest to the unknown key, if it's close enough to be a likely typo
+	pub suggestion: Option<String>,
+}
+
+impl UnknownKey {
+	/// Text used by `File::mark_unknown_keys()`
+	pub fn comment(&self) -> String {
+		match &self.suggestion {
+			Some(suggestion) => format!(" unknown setting, did you mean \"{suggestion}\"?"),
+			None => String::from(" unknown setting"),
+		}
+	}
+}
+
+impl std::fmt::Display for UnknownKey {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.line {
+			Some(line) => write!(f, "Unknown setting '{}' at line {line}", self.key)?,
+			None => write!(f, "Unknown setting '{}'", self.key)?,
+		}
+		if let Some(suggestion) = &self.suggestion {
+			write!(f, ", did you mean '{suggestion}'?")?;
+		}
+		Ok(())
+	}
+}
+
+
+
+impl File {
+	
+	/// Returns every setting whose key isn't in `known_keys`, ordered by where they appear in the file
+	/// 
+	/// Known keys can use `*` and `?` as wildcards (for example, `servers.*.name`), but keys with wildcards are never suggested
+	/// 
+	/// Suggestions are the known key with the smallest edit distance, as long as the distance is at most a third of the key's length (or 2 for short keys)
+	pub fn find_unknown_keys<'a>(&self, known_keys: impl IntoIterator<Item = &'a str>) -> Vec<UnknownKey> {
+		let known_keys = known_keys.into_iter().collect::<Vec<_>>();
+		let is_known = |key: &str| known_keys.iter().any(|known_key| crate::utils::glob_match(known_key, key));
+		let suggestions = known_keys.iter().copied().filter(|known_key| !known_key.contains(['*', '?'])).collect::<Vec<_>>();
+		
+		let line_numbers = self.layout_line_numbers();
+		let mut output = vec!();
+		for (entry, line) in self.layout.iter().zip(line_numbers) {
+			let LayoutEntry::Key (key) = entry else {continue;};
+			if !self.values.contains_key(key) || is_known(key) {continue;}
+			output.push(UnknownKey {
+				key: key.clone(),
+				line: Some(line),
+				suggestion: crate::utils::closest_typo_match(key, suggestions.iter().copied()).map(str::to_string),
+			});
+		}
+		let mut unordered_keys = self.values.keys().filter(|key| !is_known(key) && self.line_of(key).is_none()).collect::<Vec<_>>();
+		unordered_keys.sort();
+		for key in unordered_keys {
+			output.push(UnknownKey {
+				key: key.clone(),
+				line: None,
+				suggestion: crate::utils::closest_typo_match(key, suggestions.iter().copied()).map(str::to_string),
+			});
+		}
+		output
+	}
+	
+	/// Adds a comment above each unknown key so that the user can see that it isn't used (this does nothing for keys that are already marked)
+	pub fn mark_unknown_keys(&mut self, unknown_keys: &[UnknownKey]) {
+		for unknown_key in unknown_keys {
+			let comment = unknown_key.comment();
+			let key_index = self.layout_index(&unknown_key.key);
+			if let Some(key_index) = key_index && key_index > 0 && self.layout[key_index - 1] == LayoutEntry::Comment (comment.clone()) {continue;}
+			self.insert_comment_above(&unknown_key.key, comment);
+		}
+	}
+	
+}
+
+
+
+impl Schema {
+	/// Same as `File::find_unknown_keys()`, using the schema's keys as the known keys
+	pub fn find_unknown_keys(&self, file: &File) -> Vec<UnknownKey> {
+		file.find_unknown_keys(self.entries.iter().map(|entry| entry.key.as_str()))
+	}
+}
@@ -0,0 +1,139 @@
+use crate::*;
+
+
+
+/// Formats a value for messages, where multiline strings are replaced with a short description
+pub(crate) fn format_short(value: &Value) -> String {
+	match value {
+		Value::String (string) if string.contains('\n') => String::from("a multiline string"),
+		_ => value.format(),
+	}
+}
+
+
+
+/// Levenshtein distance between two strings (counted in chars)
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+	let b = b.chars().collect::<Vec<_>>();
+	let mut prev_row = (0..=b.len()).collect::<Vec<_>>();
+	let mut curr_row = vec!(0; b.len() + 1);
+	for (i, a_char) in a.chars().enumerate() {
+		curr_row[0] = i + 1;
+		for (j, b_char) in b.iter().enumerate() {
+			let substitution_cost = if a_char == *b_char {0} else {1};
+			curr_row[j + 1] = (prev_row[j] + substitution_cost).min(prev_row[j + 1] + 1).min(curr_row[j] + 1);
+		}
+		std::mem::swap(&mut prev_row, &mut curr_row);
+	}
+	prev_row[b.len()]
+}
+
+
+
+/// Returns the option with the smallest edit distance to `target` (the first one wins ties)
+pub(crate) fn closest_match<'a>(target: &str, options: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+	options.into_iter()
+		.map(|option| (edit_distance(target, option), option))
+		.min_by_key(|(distance, _option)| *distance)
+		.map(|(_distance, option)| option)
+}
+
+
+
+/// Same as `closest_match()`, but only returns options that are close enough to plausibly be a typo of `target`
+pub(crate) fn closest_typo_match<'a>(target: &str, options: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+	let max_distance = (target.chars().count() / 3).max(2);
+	let closest = closest_match(target, options)?;
+	(edit_distance(target, closest) <= max_distance).then_some(closest)
+}
+
+
+
+/// Simple wildcard matching, where `*` matches any number of chars and `?` matches exactly one char
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+	let pattern = pattern.chars().collect::<Vec<_>>();
+	let text = text.chars().collect::<Vec<_>>();
+	let (mut pattern_i, mut text_i) = (0, 0);
+	let mut last_star = None;
+	while text_i < text.len() {
+		match pattern.get(pattern_i) {
+			Some('*') => {
+				last_star = Some((pattern_i, text_i));
+				pattern_i += 1;
+			}
+			Some(c) if *c == '?' || *c == text[text_i] => {
+				pattern_i += 1;
+				text_i += 1;
+			}
+			_ => {
+				let Some((star_pattern_i, star_text_i)) = last_star else {return false;};
+				pattern_i = star_pattern_i + 1;
+				text_i = star_text_i + 1;
+				last_star = Some((star_pattern_i, star_text_i + 1));
+			}
+		}
+	}
+	pattern[pattern_i..].iter().all(|c| *c == '*')
+}
+
+
+
+/// Removes `.` and `..` components from a path without touching the file system (`..` at the start of a relative path is kept)
+pub(crate) fn normalize_path(path: &std::path::Path) -> std::path::PathBuf {
+	use std::path::Component;
+	let mut output = std::path::PathBuf::new();
+	for component in path.components() {
+		match component {
+			Component::CurDir => {}
+			Component::ParentDir => {
+				let can_pop = matches!(output.components().next_back(), Some(Component::Normal(_)));
+				if can_pop {
+					output.pop();
+				} else if !output.has_root() {
+					output.push("..");
+				}
+			}
+			_ => output.push(component),
+		}
+	}
+	output
+}
+
+
+
+/// One step of turning one sequence into another, see `diff_sequences()`
+pub(crate) enum DiffOp {
+	/// Item at this index in the old sequence is also in the new sequence
+	Same (usize),
+	/// Item at this index in the old sequence was removed
+	Removed (usize),
+	/// Item at this index in the new sequence was added
+	Added (usize),
+}
+
+/// Lists the steps that turn `old` into `new`, keeping the longest common subsequence and putting removals before additions
+pub(crate) fn diff_sequences<T: PartialEq>(old: &[T], new: &[T]) -> Vec<DiffOp> {
+	// lengths[i][j] is the length of the longest common subsequence of old[i..] and new[j..]
+	let mut lengths = vec!(vec!(0usize; new.len() + 1); old.len() + 1);
+	for i in (0..old.len()).rev() {
+		for j in (0..new.len()).rev() {
+			lengths[i][j] = if old[i] == new[j] {lengths[i + 1][j + 1] + 1} else {lengths[i + 1][j].max(lengths[i][j + 1])};
+		}
+	}
+	let mut output = vec!();
+	let (mut i, mut j) = (0, 0);
+	while i < old.len() || j < new.len() {
+		if i < old.len() && j < new.len() && old[i] == new[j] {
+			output.push(DiffOp::Same (i));
+			i += 1;
+			j += 1;
+		} else if j == new.len() || (i < old.len() && lengths[i + 1][j] >= lengths[i][j + 1]) {
+			output.push(DiffOp::Removed (i));
+			i += 1;
+		} else {
+			output.push(DiffOp::Added (j));
+			j += 1;
+		}
+	}
+	output
+}
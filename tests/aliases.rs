@@ -0,0 +1,44 @@
+mod common;
+
+use common::*;
+use easy_configuration_format::*;
+
+
+
+#[test]
+fn aliases_rename_settings_while_loading() {
+	let aliases = [
+		KeyAlias::new("volume", "audio.volume").deprecated("moved into the audio namespace"),
+		KeyAlias::new("audio.volume", "audio.master volume"),
+	];
+	let (file, _did_run_updaters, errors, warnings) = File::from_str_with_aliases::<()>("format 1\n# volume\nvolume: 5", &[], &mut (), &aliases);
+	assert!(errors.is_empty());
+	assert_eq!(warnings.len(), 2);
+	assert_eq!(warnings[0].line, Some(3));
+	assert_eq!(warnings[0].to_string(), "Setting 'volume' at line 3 has been renamed to 'audio.volume': moved into the audio namespace");
+	assert_eq!(file.to_str().0, "format 1\n# volume\naudio.master volume: 5");
+	
+	assert_eq!(file.resolve_key("volume"), "audio.master volume");
+	assert_eq!(file.get_int("volume"), Ok(5));
+	assert_eq!(file.get_int("audio.volume"), Ok(5));
+}
+
+#[test]
+fn conflicting_old_settings_are_commented_out() {
+	let mut file = parse("format 1\nvolume: 5\naudio.volume: 7");
+	let warnings = file.apply_aliases([KeyAlias::new("volume", "audio.volume")]);
+	assert!(warnings[0].conflict);
+	assert_eq!(file.get_int("audio.volume"), Ok(7));
+	assert_eq!(file.to_str().0, "format 1\n# volume: 5 (replaced by \"audio.volume\")\naudio.volume: 7");
+}
+
+#[test]
+fn repairs_use_the_new_key() {
+	let mut file = parse("format 1\nthreads: \"x\"");
+	file.apply_aliases([KeyAlias::new("cores", "threads")]);
+	let (threads, repair) = file.get_or_repair::<i64>("cores", 4);
+	assert_eq!(threads, 4);
+	assert_eq!(repair.unwrap().key, "threads");
+	assert_eq!(file.values.get("cores"), None);
+	assert_eq!(file.to_str().0, "format 1\n# invalid value (reset to default): threads: \"x\"\nthreads: 4");
+}
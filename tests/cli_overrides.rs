@@ -0,0 +1,46 @@
+mod common;
+
+use common::*;
+use easy_configuration_format::*;
+use std::path::{Path, PathBuf};
+
+
+
+fn read_file(path: &Path) -> std::io::Result<String> {
+	match path.to_str() {
+		Some("motd.txt") => Ok(String::from("line 1\nline 2\n")),
+		_ => Err(std::io::Error::new(std::io::ErrorKind::NotFound, "not found")),
+	}
+}
+
+
+
+#[test]
+fn set_and_config_arguments_are_recognized() {
+	let args = ["--verbose", "--set", "volume=-3", "--set=name=\"cli\"", "--config", "a.ecf", "--set", "motd=@motd.txt", "--config=b.ecf", "--", "--set", "x=1"];
+	let (cli, errors) = CliOverrides::parse_with_reader(args, read_file);
+	assert!(errors.is_empty());
+	assert_eq!(cli.overrides.get_int("volume"), Ok(-3));
+	assert_eq!(cli.overrides.get_str("name"), Ok("cli"));
+	assert_eq!(cli.overrides.get_str("motd"), Ok("line 1\nline 2"));
+	assert_eq!(cli.config_paths, vec!(PathBuf::from("a.ecf"), PathBuf::from("b.ecf")));
+	assert_eq!(cli.remaining, vec!("--verbose", "--", "--set", "x=1"));
+	
+	let mut file = parse("format 1\nvolume: 5");
+	cli.apply(&mut file);
+	assert_eq!(file.get_int("volume"), Ok(-3));
+}
+
+#[test]
+fn invalid_arguments_are_reported() {
+	let args = ["--set", "volume", "--set", "=1", "--set", "a: b=1", "--set", "notes=@missing.txt", "--set", "name=cli", "--set"];
+	let (cli, errors) = CliOverrides::parse_with_reader(args, read_file);
+	assert!(cli.overrides.values.is_empty());
+	assert_eq!(errors.len(), 6);
+	assert_eq!(errors[0].argument, "--set volume");
+	assert_eq!(errors[0].message, "Expected \"key=value\"");
+	assert_eq!(errors[1].message, "Key cannot be empty");
+	assert_eq!(errors[2].message, "Keys cannot contain colons");
+	assert!(errors[3].message.starts_with("Could not read file \"missing.txt\""));
+	assert_eq!(errors[5].message, "Expected a value after --set");
+}
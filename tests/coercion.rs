@@ -0,0 +1,56 @@
+mod common;
+
+use common::*;
+use easy_configuration_format::*;
+
+
+
+#[test]
+fn strict_by_default() {
+	let file = parse("format 1\nvolume: \"75\"");
+	assert!(matches!(file.get_int("volume"), Err(RetrieveSettingError::WrongSingularType { .. })));
+	assert!(file.coercion_warnings().is_empty());
+}
+
+#[test]
+fn lenient_files_coerce_and_record_warnings() {
+	let mut file = parse("format 1\nvolume: \"75\"\nenabled: \"yes\"\nmuted: \"off\"\nratio: 2\nspeed: \"0.5\"");
+	file.coercion = Coercion::LENIENT;
+	assert_eq!(file.get_int("volume"), Ok(75));
+	assert_eq!(file.get_bool("enabled"), Ok(true));
+	assert_eq!(file.get_bool("muted"), Ok(false));
+	assert_eq!(file.get_float("ratio"), Ok(2.0));
+	assert_eq!(file.get::<f64>("speed"), Ok(0.5));
+	let warnings = file.coercion_warnings();
+	assert_eq!(warnings.len(), 5);
+	assert_eq!(warnings[0], CoercionWarning { key: String::from("volume"), original: Value::String (String::from("75")), coerced: Value::I64 (75) });
+	file.clear_coercion_warnings();
+	assert!(file.coercion_warnings().is_empty());
+}
+
+#[test]
+fn coercion_can_be_chosen_per_call() {
+	let file = parse("format 1\nvolume: \"75\"\nname: \"abc\"");
+	assert_eq!(file.get_coerced::<i64>("volume", Coercion::LENIENT), Ok(75));
+	assert!(file.get_coerced::<i64>("name", Coercion::LENIENT).is_err());
+	assert!(file.get::<i64>("volume").is_err());
+}
+
+#[test]
+fn coerced_values_can_be_rewritten_on_save() {
+	let mut file = parse("format 1\nvolume: \"75\"");
+	file.coercion = Coercion { rewrite_on_save: true, ..Coercion::LENIENT };
+	assert_eq!(file.to_str().0, "format 1\nvolume: \"75\"");
+	file.get_int("volume").unwrap();
+	assert_eq!(file.to_str().0, "format 1\nvolume: 75");
+}
+
+#[test]
+fn comparing_files_ignores_bookkeeping() {
+	let text = "format 1\nvolume: \"75\"";
+	let plain = parse(text);
+	let mut lenient = parse(text);
+	lenient.coercion = Coercion::LENIENT;
+	lenient.get_int("volume").unwrap();
+	assert_eq!(plain, lenient);
+}
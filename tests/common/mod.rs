@@ -0,0 +1,22 @@
+// each test file only uses some of these helpers
+#![allow(dead_code)]
+
+use easy_configuration_format::*;
+use std::{fs, path::PathBuf};
+
+
+
+/// Parses a file that shouldn't have any errors
+pub fn parse(contents: &str) -> File {
+	let (file, _did_run_updaters, errors) = File::from_str::<()>(contents, &[], &mut ());
+	assert!(errors.is_empty(), "{errors:?}");
+	file
+}
+
+/// Creates an empty directory for a test, which the test should remove once it's done
+pub fn temp_dir(name: &str) -> PathBuf {
+	let dir = std::env::temp_dir().join(format!("ecf-test-{}-{name}", std::process::id()));
+	let _ = fs::remove_dir_all(&dir);
+	fs::create_dir_all(&dir).unwrap();
+	dir
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use common::*;
+use easy_configuration_format::*;
+
+
+
+#[test]
+fn setting_changes_are_separated_from_layout_changes() {
+	let old = parse("format 1\n# audio\nvolume: 50\nname: \"app\"\nold: true");
+	let new = parse("format 1\n# sound\nvolume: 60\nname: \"app\"\nnew: false");
+	let diff = old.diff(&new);
+	assert_eq!(diff.added, vec!((String::from("new"), Value::Bool (false))));
+	assert_eq!(diff.removed, vec!((String::from("old"), Value::Bool (true))));
+	assert_eq!(diff.changed, vec!(ChangedSetting { key: String::from("volume"), old: Value::I64 (50), new: Value::I64 (60) }));
+	assert_eq!(diff.layout_changes, vec!(
+		LayoutChange::Removed { line: 2, entry: LayoutEntry::Comment (String::from(" audio")) },
+		LayoutChange::Added { line: 2, entry: LayoutEntry::Comment (String::from(" sound")) },
+	));
+	assert!(diff.has_setting_changes());
+	
+	let comments_only = parse("format 1\n# audio\nvolume: 50\n\nname: \"app\"\nold: true").diff(&old);
+	assert!(!comments_only.is_empty());
+	assert!(!comments_only.has_setting_changes());
+	assert!(old.diff(&old.clone()).is_empty());
+	assert_eq!(old.diff(&old).to_string(), "");
+}
+
+#[test]
+fn diffs_are_printed_as_unified_diffs() {
+	let old = parse("format 1\n# audio\nvolume: 50\nname: \"app\"\na: 1\nb: 2\nc: 3\nd: 4\ne: 5\nf: 6\nold: true");
+	let new = parse("format 1\n# sound\nvolume: 60\nname: \"app\"\na: 1\nb: 2\nc: 3\nd: 4\ne: 5\nf: 6\nnew: false");
+	let diff = old.diff(&new);
+	assert_eq!(diff.to_unified_string(1), "@@ -1,4 +1,4 @@\n format 1\n-# audio\n-volume: 50\n+# sound\n+volume: 60\n name: \"app\"\n@@ -10,2 +10,2 @@\n f: 6\n-old: true\n+new: false\n");
+	assert!(diff.to_string().starts_with("@@ -1,6 +1,6 @@\n"));
+	
+	// an empty range starts at the line before it
+	let diff = parse("format 1\na: 1").diff(&parse("format 1\na: 1\nb: 2"));
+	assert_eq!(diff.to_unified_string(0), "@@ -2,0 +3,1 @@\n+b: 2\n");
+}
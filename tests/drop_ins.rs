@@ -0,0 +1,37 @@
+use easy_configuration_format::*;
+use std::path::{Path, PathBuf};
+
+
+
+#[test]
+fn later_fragments_override_earlier_ones() {
+	assert_eq!(drop_in_dir("etc/app.ecf"), PathBuf::from("etc/app.ecf.d"));
+	let loader = MemoryLoader::new()
+		.with_file("etc/app.ecf", "format 1\nvolume: 50\nname: \"app\"")
+		.with_file("etc/app.ecf.d/20-loud.ecf", "format 1\nvolume: 90")
+		.with_file("etc/app.ecf.d/10-quiet.ecf", "format 1\nvolume: 10\ntheme: \"dark\"")
+		.with_file("etc/app.ecf.d/README", "not settings");
+	let (mut set, errors) = FileSet::load_dir("etc/app.ecf", &loader).unwrap();
+	assert!(errors.is_empty());
+	assert_eq!(set.files.iter().map(|source| source.path.as_path()).collect::<Vec<_>>(), vec!(Path::new("etc/app.ecf"), Path::new("etc/app.ecf.d/10-quiet.ecf"), Path::new("etc/app.ecf.d/20-loud.ecf")));
+	assert_eq!(set.get::<i64>("volume"), Ok(90));
+	assert_eq!(set.get::<String>("theme"), Ok(String::from("dark")));
+	assert_eq!(set.sources_of("volume").iter().map(|source| source.path.as_path()).collect::<Vec<_>>(), vec!(Path::new("etc/app.ecf.d/20-loud.ecf"), Path::new("etc/app.ecf.d/10-quiet.ecf"), Path::new("etc/app.ecf")));
+	
+	// removing the winning definition falls back to the next fragment
+	assert_eq!(set.remove("volume"), Some(Value::I64 (90)));
+	assert_eq!(set.get::<i64>("volume"), Ok(10));
+}
+
+#[test]
+fn the_fragment_directory_is_optional() {
+	let loader = MemoryLoader::new().with_file("app.ecf", "format 1\nvolume: 50");
+	let (set, errors) = FileSet::load_dir("app.ecf", &loader).unwrap();
+	assert!(errors.is_empty());
+	assert_eq!(set.files.len(), 1);
+	
+	let loader = loader.with_file("app.ecf.d/a.ecf", "volume: 5");
+	let (_set, errors) = FileSet::load_dir("app.ecf", &loader).unwrap();
+	assert_eq!(errors.len(), 1);
+	assert_eq!(errors[0].message, "Could not find version");
+}
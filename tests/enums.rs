@@ -0,0 +1,44 @@
+mod common;
+
+use common::*;
+use easy_configuration_format::*;
+
+
+
+ecf_enum! {
+	#[derive(Debug, Clone, Copy, PartialEq)]
+	pub enum LogLevel {
+		Debug = "debug" | "verbose",
+		Info = "info",
+		Error = "error",
+	}
+}
+
+
+
+#[test]
+fn spellings_and_aliases_are_accepted() {
+	let file = parse("format 1\na: \"debug\"\nb: \"verbose\"\nc: \"error\"");
+	assert_eq!(file.get_enum::<LogLevel>("a"), Ok(LogLevel::Debug));
+	assert_eq!(file.get_enum::<LogLevel>("b"), Ok(LogLevel::Debug));
+	assert_eq!(file.get::<LogLevel>("c"), Ok(LogLevel::Error));
+}
+
+#[test]
+fn values_are_written_with_the_canonical_spelling() {
+	let mut file = parse("format 1\nlevel: \"verbose\"");
+	let level = file.get_enum::<LogLevel>("level").unwrap();
+	file.values.insert(String::from("level"), level.into_value());
+	assert_eq!(file.to_str().0, "format 1\nlevel: \"debug\"");
+}
+
+#[test]
+fn invalid_values_list_the_allowed_spellings_and_close_matches() {
+	let file = parse("format 1\ntypo: \"degub\"\nunrelated: \"zzzzzzzzzz\"\nnumber: 3");
+	let Err(RetrieveSettingError::InvalidVariant { allowed, closest, .. }) = file.get_enum::<LogLevel>("typo") else {panic!("expected an invalid variant error")};
+	assert_eq!(allowed, ["debug", "verbose", "info", "error"]);
+	assert_eq!(closest.as_deref(), Some("debug"));
+	let Err(RetrieveSettingError::InvalidVariant { closest, .. }) = file.get_enum::<LogLevel>("unrelated") else {panic!("expected an invalid variant error")};
+	assert_eq!(closest, None);
+	assert!(matches!(file.get_enum::<LogLevel>("number"), Err(RetrieveSettingError::WrongSingularType { .. })));
+}
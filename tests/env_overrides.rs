@@ -0,0 +1,43 @@
+mod common;
+
+use common::*;
+use easy_configuration_format::*;
+
+
+
+#[test]
+fn variables_map_onto_dotted_keys() {
+	let env = EnvOverrides::new("APP_");
+	assert_eq!(env.key_for("APP_WINDOW__WIDTH").as_deref(), Some("window.width"));
+	assert_eq!(env.key_for("OTHER_WIDTH"), None);
+	assert_eq!(EnvOverrides::new("APP_").word_separator(" ").key_for("APP_LOG_LEVEL").as_deref(), Some("log level"));
+	
+	let (overrides, errors) = env.read_vars([
+		("APP_WINDOW__WIDTH", "800"),
+		("APP_OFFSET", "-1"),
+		("APP_SCALE", "-0.5"),
+		("APP_NAME", "\"example\""),
+		("HOME", "/home/user"),
+	]);
+	assert!(errors.is_empty());
+	assert_eq!(overrides.get_int("window.width"), Ok(800));
+	assert_eq!(overrides.get_int("offset"), Ok(-1));
+	assert_eq!(overrides.get_float("scale"), Ok(-0.5));
+	assert_eq!(overrides.get_str("name"), Ok("example"));
+	assert_eq!(overrides.values.len(), 4);
+	
+	let mut file = parse("format 1\noffset: 3\nname: \"app\"");
+	file.apply_overrides(&overrides);
+	assert_eq!(file.get_int("offset"), Ok(-1));
+	assert_eq!(file.get_str("name"), Ok("example"));
+}
+
+#[test]
+fn invalid_variables_are_reported() {
+	let env = EnvOverrides::new("APP_");
+	let (overrides, errors) = env.read_vars([("APP_NAME", "example"), ("APP_A____B", "1"), ("APP_OK", "true")]);
+	assert_eq!(overrides.get_bool("ok"), Ok(true));
+	assert_eq!(errors.len(), 2);
+	assert_eq!(errors[0].variable, "APP_A____B");
+	assert_eq!(errors[1].variable, "APP_NAME");
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use common::*;
+use easy_configuration_format::*;
+use std::fs;
+
+
+
+fn add_volume(values: &mut std::collections::HashMap<String, Value>, _args: &mut ()) {
+	values.insert(String::from("volume"), Value::I64 (50));
+}
+
+
+
+#[test]
+fn files_are_loaded_and_saved() {
+	let dir = temp_dir("load-save");
+	let path = dir.join("settings.ecf");
+	fs::write(&path, "format 1\n# name\nname: \"app\"\nbroken line\n").unwrap();
+	let (mut file, did_run_updaters, errors) = File::load::<()>(&path, &[], &mut ()).unwrap();
+	assert!(!did_run_updaters);
+	assert_eq!(errors.len(), 1);
+	assert!(matches!(&errors[0], FileError::Parse { error, .. } if error.line == 4));
+	assert_eq!(errors[0].path(), path);
+	
+	file.set("name", "changed");
+	let report = file.save(&path).unwrap();
+	assert!(report.format_errors.is_empty());
+	assert!(!report.merged_changes);
+	assert_eq!(fs::read_to_string(&path).unwrap(), file.to_str().0);
+	assert!(fs::read_to_string(&path).unwrap().contains("name: \"changed\""));
+	
+	// only the settings file is left, without any temporary files
+	assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+	assert!(matches!(File::load::<()>(dir.join("missing.ecf"), &[], &mut ()), Err(FileError::Io { .. })));
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn unclosed_multiline_comments_are_errors() {
+	let dir = temp_dir("unclosed-comment");
+	let path = dir.join("settings.ecf");
+	fs::write(&path, "format 1\na: 1\n##").unwrap();
+	let (mut file, _did_run_updaters, errors) = File::load::<()>(&path, &[], &mut ()).unwrap();
+	assert_eq!(errors.len(), 1);
+	assert!(matches!(&errors[0], FileError::Parse { error, .. } if error.line == 3 && error.message.starts_with("Could not find an end of this multiline comment")));
+	assert_eq!(file.get_int("a"), Ok(1));
+	
+	// the same file on disk is also reported when saving merges in changes made on disk
+	fs::write(&path, "format 1\na: 2\n##").unwrap();
+	file.set("a", 3);
+	assert!(matches!(file.save(&path), Err(FileError::Parse { error, .. }) if error.line == 3));
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn backups_keep_the_previous_text() {
+	let dir = temp_dir("backups");
+	let path = dir.join("settings.ecf");
+	fs::write(&path, "format 1\nname: \"app\"").unwrap();
+	let (mut file, did_run_updaters, _errors) = File::load(&path, &[add_volume], &mut ()).unwrap();
+	assert!(did_run_updaters);
+	
+	let options = SaveOptions { backup: Backup::PreMigration, ..SaveOptions::default() };
+	file.save_with_options(&path, options).unwrap();
+	assert_eq!(backup_path(&path), dir.join("settings.ecf.bak"));
+	assert_eq!(fs::read_to_string(backup_path(&path)).unwrap(), "format 1\nname: \"app\"");
+	assert_eq!(fs::read_to_string(&path).unwrap(), "format 2\nname: \"app\"\nvolume: 50");
+	
+	// the pre-migration backup is only written once
+	file.set("name", "changed");
+	file.save_with_options(&path, options).unwrap();
+	assert_eq!(fs::read_to_string(backup_path(&path)).unwrap(), "format 1\nname: \"app\"");
+	
+	file.set("name", "again");
+	file.save_with_options(&path, SaveOptions { backup: Backup::PreviousVersion, ..SaveOptions::default() }).unwrap();
+	assert_eq!(fs::read_to_string(backup_path(&path)).unwrap(), "format 2\nname: \"changed\"\nvolume: 50");
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn aliases_are_applied_while_loading() {
+	let dir = temp_dir("aliases");
+	let path = dir.join("settings.ecf");
+	fs::write(&path, "format 1\nvolume: 5").unwrap();
+	let (file, _did_run_updaters, errors, warnings) = File::load_with_aliases::<()>(&path, &[], &mut (), &[KeyAlias::new("volume", "audio.volume")]).unwrap();
+	assert!(errors.is_empty());
+	assert_eq!(warnings.len(), 1);
+	assert_eq!(file.to_str().0, "format 1\naudio.volume: 5");
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn concurrent_saves_dont_collide() {
+	let dir = temp_dir("concurrent");
+	let path = dir.join("settings.ecf");
+	std::thread::scope(|scope| {
+		for i in 0..8 {
+			let path = &path;
+			scope.spawn(move || {
+				for j in 0..20 {
+					let mut file = File::new(1);
+					file.set("writer", i * 100 + j);
+					file.save_with_options(path, SaveOptions { merge_changes: false, ..SaveOptions::default() }).unwrap();
+				}
+			});
+		}
+	});
+	let (file, _did_run_updaters, errors) = File::load::<()>(&path, &[], &mut ()).unwrap();
+	assert!(errors.is_empty());
+	assert!(file.get_int("writer").is_ok());
+	assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+	fs::remove_dir_all(&dir).unwrap();
+}
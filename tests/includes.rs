@@ -0,0 +1,69 @@
+use easy_configuration_format::*;
+use std::path::Path;
+
+
+
+fn load(loader: &MemoryLoader) -> (FileSet, Vec<SourceFileError>) {
+	FileSet::load_with_includes("app/main.ecf", loader, IncludeOptions::default()).unwrap()
+}
+
+
+
+#[test]
+fn included_settings_are_saved_back_to_their_file() {
+	let loader = MemoryLoader::new()
+		.with_file("app/main.ecf", "format 1\nname: \"app\"\ninclude \"net.ecf\"\ninclude \"conf.d/*.ecf\"")
+		.with_file("app/net.ecf", "format 1\nport: 80")
+		.with_file("app/conf.d/b.ecf", "format 1\nb: 2")
+		.with_file("app/conf.d/a.ecf", "format 1\na: 1")
+		.with_file("app/conf.d/notes.txt", "not settings");
+	let (mut set, errors) = load(&loader);
+	assert!(errors.is_empty());
+	assert_eq!(set.files.len(), 4);
+	assert_eq!(set.get::<i64>("port"), Ok(80));
+	assert_eq!(set.source_of("a").unwrap().to_string(), "line 2 of \"app/conf.d/a.ecf\"");
+	assert_eq!(set.to_file().to_str().0, "format 1\nname: \"app\"\nport: 80\na: 1\nb: 2");
+	
+	set.set("port", 8080);
+	set.set("new", true);
+	assert_eq!(set.changed_files(), vec!(Path::new("app/main.ecf"), Path::new("app/net.ecf")));
+	assert!(set.save(&loader).is_empty());
+	assert_eq!(loader.get("app/net.ecf").unwrap(), "format 1\nport: 8080");
+	assert_eq!(loader.get("app/main.ecf").unwrap(), "format 1\nname: \"app\"\ninclude \"net.ecf\"\ninclude \"conf.d/*.ecf\"\nnew: true");
+	assert!(set.changed_files().is_empty());
+}
+
+#[test]
+fn cycles_and_deep_includes_are_reported() {
+	let loader = MemoryLoader::new()
+		.with_file("app/main.ecf", "format 1\ninclude \"a.ecf\"")
+		.with_file("app/a.ecf", "format 1\na: 1\ninclude \"main.ecf\"\ninclude \"missing.ecf\"");
+	let (set, errors) = load(&loader);
+	assert_eq!(set.files.len(), 2);
+	assert_eq!(errors.len(), 2);
+	assert_eq!(errors[0].message, "Include cycle found: \"app/main.ecf\" -> \"app/a.ecf\" -> \"app/main.ecf\"");
+	assert_eq!(errors[0].line, Some(3));
+	assert!(errors[1].message.starts_with("Could not include \"app/missing.ecf\""));
+	
+	let loader = MemoryLoader::new()
+		.with_file("app/main.ecf", "format 1\ninclude \"a.ecf\"")
+		.with_file("app/a.ecf", "format 1\ninclude \"b.ecf\"")
+		.with_file("app/b.ecf", "format 1\nb: 1");
+	let (set, errors) = FileSet::load_with_includes("app/main.ecf", &loader, IncludeOptions { max_depth: 1 }).unwrap();
+	assert_eq!(set.files.len(), 2);
+	assert_eq!(errors[0].message, "Includes cannot be nested more than 1 levels deep");
+	
+	assert!(FileSet::load_with_includes("app/missing.ecf", &loader, IncludeOptions::default()).is_err());
+}
+
+#[test]
+fn duplicate_settings_and_versions_are_reported() {
+	let loader = MemoryLoader::new()
+		.with_file("app/main.ecf", "format 2\nport: 1\ninclude \"net.ecf\"")
+		.with_file("app/net.ecf", "format 1\nport: 2");
+	let (set, errors) = load(&loader);
+	assert_eq!(set.get::<i64>("port"), Ok(1));
+	assert_eq!(errors.len(), 2);
+	assert_eq!(errors[0].to_string(), "Error in \"app/net.ecf\" at line 1: Format 1 does not match the root file's format (2)");
+	assert_eq!(errors[1].message, "Key \"port\" is already defined in \"app/main.ecf\", so this definition is ignored");
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use common::*;
+use easy_configuration_format::*;
+
+
+
+fn env(name: &str) -> Option<String> {
+	match name {
+		"HOME" => Some(String::from("/home/user")),
+		_ => None,
+	}
+}
+
+
+
+#[test]
+fn references_are_expanded_recursively() {
+	let file = parse("format 1\ndata dir: \"${env:HOME}/data\"\ncache dir: \"${data dir}/cache\"\nimages: \"${cache dir}/images on port ${port}${nothing}\"\nport: 8080\nnothing: empty\nprice: \"$5, $${not a reference}\"");
+	assert_eq!(file.get_interpolated_with_env("images", env), Ok(String::from("/home/user/data/cache/images on port 8080")));
+	assert_eq!(file.get_interpolated_with_env("price", env), Ok(String::from("$5, ${not a reference}")));
+	assert_eq!(file.get_str("cache dir"), Ok("${data dir}/cache"));
+	
+	let (expanded, errors) = file.interpolated_with_env(env);
+	assert!(errors.is_empty());
+	assert_eq!(expanded.get_str("cache dir"), Ok("/home/user/data/cache"));
+	assert_eq!(expanded.get_int("port"), Ok(8080));
+}
+
+#[test]
+fn escaped_text_is_kept_as_it_is() {
+	let escaped = escape_interpolation("cost: ${price}");
+	assert_eq!(escaped, "cost: $${price}");
+	let mut file = parse("format 1");
+	file.set("text", escaped);
+	assert_eq!(file.get_interpolated("text"), Ok(String::from("cost: ${price}")));
+}
+
+#[test]
+fn invalid_references_are_reported() {
+	let file = parse("format 1\na: \"${b}\"\nb: \"${a}\"\nc: \"${missing}\"\nd: \"${env:NOPE}\"\ne: \"${unclosed\"\nf: \"${}\"");
+	let err = file.get_interpolated_with_env("a", env).unwrap_err();
+	assert_eq!(err.message, "Reference cycle found: \"a\" -> \"b\" -> \"a\"");
+	assert_eq!(file.get_interpolated_with_env("c", env).unwrap_err().to_string(), "Could not expand setting 'c' at line 4: Referenced setting \"missing\" does not exist");
+	assert_eq!(file.get_interpolated_with_env("d", env).unwrap_err().message, "Environment variable \"NOPE\" is not set");
+	assert!(file.get_interpolated_with_env("e", env).unwrap_err().message.starts_with("Could not find the end of a reference"));
+	assert_eq!(file.get_interpolated_with_env("f", env).unwrap_err().message, "References cannot be empty");
+	
+	let (expanded, errors) = file.interpolated_with_env(env);
+	assert_eq!(errors.len(), 6);
+	assert_eq!(expanded.get_str("a"), Ok("${b}"));
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use common::*;
+use easy_configuration_format::*;
+
+
+
+fn example_config() -> LayeredConfig {
+	LayeredConfig::new()
+		.with_layer("user", LayeredConfig::USER_PRIORITY, parse("format 1\nvolume: 80"))
+		.with_layer("defaults", LayeredConfig::DEFAULTS_PRIORITY, parse("format 1\nvolume: 50\nname: \"app\""))
+		.with_layer("overrides", LayeredConfig::OVERRIDES_PRIORITY, parse("format 1\n\nname: \"cli\""))
+}
+
+
+
+#[test]
+fn higher_priority_layers_win() {
+	let config = example_config();
+	assert_eq!(config.layers().iter().map(|layer| layer.name.as_str()).collect::<Vec<_>>(), vec!("defaults", "user", "overrides"));
+	assert_eq!(config.get_int("volume"), Ok(80));
+	assert_eq!(config.get_str("name"), Ok("cli"));
+	assert_eq!(config.keys(), vec!("name", "volume"));
+	assert!(config.get_int("missing").is_err());
+	
+	assert_eq!(config.provenance("name"), Some(Provenance { layer: String::from("overrides"), line: Some(3) }));
+	assert_eq!(config.provenance("volume").unwrap().to_string(), "line 2 of layer \"user\"");
+	assert_eq!(config.provenance("missing"), None);
+}
+
+#[test]
+fn layers_are_edited_and_saved_individually() {
+	let mut config = example_config();
+	assert_eq!(config.set("user", "name", "mine"), Ok(None));
+	assert_eq!(config.to_str("user").unwrap().0, "format 1\nvolume: 80\nname: \"mine\"");
+	assert_eq!(config.to_str("defaults").unwrap().0, "format 1\nvolume: 50\nname: \"app\"");
+	assert_eq!(config.set("nope", "name", "x"), Err(UnknownLayerError { layer: String::from("nope") }));
+	
+	config.remove_layer("overrides");
+	assert_eq!(config.get_str("name"), Ok("mine"));
+	let flattened = config.flatten();
+	assert_eq!(flattened.to_str().0, "format 1\nvolume: 80\nname: \"mine\"");
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use common::*;
+use easy_configuration_format::*;
+use std::fs;
+
+
+
+#[test]
+fn changes_on_both_sides_are_merged() {
+	let base = parse("format 1\n# audio\nvolume: 50\nname: \"app\"\ntheme: \"light\"");
+	let ours = parse("format 1\n# audio\nvolume: 80\nname: \"app\"\ntheme: \"dark\"\nmine: 1");
+	let theirs = parse("format 1\n# sound\nvolume: 50\nname: \"edited\"\ntheme: \"blue\"\n# theirs\ntheirs: 2");
+	let (merged, conflicts) = File::merge_three_way(&base, &ours, &theirs);
+	assert_eq!(merged.to_str().0, "format 1\n# sound\nvolume: 80\nname: \"edited\"\ntheme: \"dark\"\nmine: 1\n# theirs\ntheirs: 2");
+	assert_eq!(conflicts, vec!(MergeConflict {
+		key: String::from("theme"),
+		base: Some(Value::String (String::from("light"))),
+		ours: Some(Value::String (String::from("dark"))),
+		theirs: Some(Value::String (String::from("blue"))),
+	}));
+	assert_eq!(conflicts[0].to_string(), "Setting 'theme' was changed from \"light\" to \"dark\" in one version and to \"blue\" in the other");
+}
+
+#[test]
+fn saving_merges_changes_made_on_disk() {
+	let dir = temp_dir("save-merge");
+	let path = dir.join("settings.ecf");
+	fs::write(&path, "format 1\nvolume: 50\nname: \"app\"").unwrap();
+	let (mut file, _did_run_updaters, _errors) = File::load::<()>(&path, &[], &mut ()).unwrap();
+	file.set("volume", 80);
+	fs::write(&path, "format 1\nvolume: 60\nname: \"edited\"").unwrap();
+	
+	let report = file.save(&path).unwrap();
+	assert!(report.merged_changes);
+	assert_eq!(report.conflicts.len(), 1);
+	assert_eq!(report.conflicts[0].key, "volume");
+	assert_eq!(fs::read_to_string(&path).unwrap(), "format 1\nvolume: 80\nname: \"edited\"");
+	assert_eq!(file.get_str("name"), Ok("edited"));
+	
+	// unchanged files aren't merged again
+	assert!(!file.save(&path).unwrap().merged_changes);
+	
+	// edits that can't be parsed are never overwritten
+	fs::write(&path, "format 1\nvolume 80").unwrap();
+	assert!(matches!(file.save(&path), Err(FileError::Parse { .. })));
+	assert_eq!(fs::read_to_string(&path).unwrap(), "format 1\nvolume 80");
+	let report = file.save_with_options(&path, SaveOptions { merge_changes: false, ..SaveOptions::default() }).unwrap();
+	assert!(!report.merged_changes);
+	assert_eq!(fs::read_to_string(&path).unwrap(), "format 1\nvolume: 80\nname: \"edited\"");
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn new_defaults_are_merged_into_user_files() {
+	let old_defaults = parse("format 1\nvolume: 50\ntheme: \"light\"\nlegacy: true\nold mode: 1");
+	let mut new_defaults = parse("format 1\nvolume: 60\ntheme: \"dark\"\n# new\nfps: 60");
+	new_defaults.version = 2;
+	let user = parse("format 1\nvolume: 70\ntheme: \"light\"\nlegacy: true\nold mode: 2\n# mine\nmine: 1");
+	
+	let (merged, conflicts) = File::merge_defaults(&old_defaults, &new_defaults, &user, RemovedDefaults::Drop);
+	assert_eq!(merged.to_str().0, "format 2\nvolume: 70\ntheme: \"dark\"\n# mine\nmine: 1\n# new\nfps: 60");
+	assert_eq!(conflicts.iter().map(|conflict| conflict.key.as_str()).collect::<Vec<_>>(), vec!("old mode", "volume"));
+	assert_eq!(conflicts[0].theirs, None);
+	assert_eq!(conflicts[1].theirs, Some(Value::I64 (60)));
+	
+	let (merged, conflicts) = File::merge_defaults(&old_defaults, &new_defaults, &user, RemovedDefaults::CommentOut);
+	assert_eq!(merged.to_str().0, "format 2\nvolume: 70\ntheme: \"dark\"\n# legacy: true\n# old mode: 2\n# mine\nmine: 1\n# new\nfps: 60");
+	assert_eq!(conflicts.len(), 2);
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use common::*;
+use easy_configuration_format::*;
+
+
+
+fn rename_volume(file: &mut File, _context: &mut MigrationContext<usize>) -> Result<(), MigrationError> {
+	let Some(value) = file.values.remove("volume") else {return Ok(());};
+	file.values.insert(String::from("audio.volume"), value);
+	if let Some(index) = file.layout.iter().position(|entry| *entry == LayoutEntry::Key (String::from("volume"))) {
+		file.layout[index] = LayoutEntry::Key (String::from("audio.volume"));
+	}
+	Ok(())
+}
+
+fn double_volume(file: &mut File, context: &mut MigrationContext<usize>) -> Result<(), MigrationError> {
+	*context.args += 1;
+	let Ok(volume) = file.get_int("audio.volume") else {
+		return Err(context.error("volume is not an int"));
+	};
+	file.values.insert(String::from("audio.volume"), Value::I64 (volume * 2));
+	Ok(())
+}
+
+
+
+#[test]
+fn migrations_edit_the_whole_file() {
+	let mut step_count = 0;
+	let (file, result, errors) = File::from_str_with_migrations("format 1\n# volume\nvolume: 5", &[&rename_volume, &double_volume], &mut step_count);
+	assert!(errors.is_empty());
+	assert!(result.unwrap().did_run_updaters());
+	assert_eq!(step_count, 1);
+	assert_eq!(file.to_str().0, "format 3\n# volume\naudio.volume: 10");
+}
+
+#[test]
+fn failed_migrations_are_rolled_back() {
+	let mut step_count = 0;
+	let (file, result, _errors) = File::from_str_with_migrations("format 1\nvolume: \"loud\"", &[&rename_volume, &double_volume], &mut step_count);
+	let err = result.unwrap_err();
+	assert_eq!(err, MigrationError::new(2, 3, "volume is not an int"));
+	assert_eq!(file.version, 1);
+	assert_eq!(file.to_str().0, "format 1\nvolume: \"loud\"");
+	
+	let mut file = file;
+	assert!(file.migrate(&[&rename_volume], &mut step_count).is_ok());
+	assert!(file.migrate::<usize>(&[], &mut step_count).is_err());
+}
+
+#[test]
+fn reports_list_every_change() {
+	let mut file = parse("format 1\nvolume: 5\nold: true");
+	let mut step_count = 0;
+	let report = file.migrate_dry_run(&[&rename_volume, &double_volume], &mut step_count).unwrap();
+	assert_eq!(file.version, 1);
+	assert_eq!(file.to_str().0, "format 1\nvolume: 5\nold: true");
+	assert_eq!(report.steps.len(), 2);
+	assert_eq!(report.steps[0].changes, vec!(KeyChange::Renamed { from: String::from("volume"), to: String::from("audio.volume"), value: Value::I64 (5) }));
+	assert_eq!(report.to_string(), "Settings were migrated from format 1 to format 3:\n  format 1 -> 2:\n    renamed 'volume' to 'audio.volume'\n  format 2 -> 3:\n    changed 'audio.volume' from 5 to 10");
+	
+	assert_eq!(file.migrate(&[&rename_volume, &double_volume], &mut step_count), Ok(report));
+	let report = file.migrate(&[&rename_volume, &double_volume], &mut step_count).unwrap();
+	assert!(!report.did_run_updaters());
+	assert_eq!(report.to_string(), "Settings are already at format 3");
+}
+
+fn halve_volume(file: &mut File, _context: &mut MigrationContext<usize>) -> Result<(), MigrationError> {
+	let volume = file.get_int("audio.volume").unwrap_or(0);
+	file.values.insert(String::from("audio.volume"), Value::I64 (volume / 2));
+	file.values.remove("audio.muted");
+	Ok(())
+}
+
+#[test]
+fn downgraded_settings_are_stashed_and_restored() {
+	let (mut file, _did_run_updaters, _errors) = File::from_str::<()>("format 3\naudio.volume: 10\naudio.muted: true", &[], &mut ());
+	assert_eq!(file.version, 3);
+	let mut step_count = 0;
+	let report = file.downgrade(2, &[None, Some(&halve_volume)], &mut step_count).unwrap();
+	assert_eq!(report.steps[0].changes.len(), 2);
+	assert_eq!(file.to_str().0, "format 2\naudio.volume: 5\n# stashed by downgrade from format 3: audio.muted: true");
+	
+	let err = file.downgrade(1, &[None, Some(&halve_volume)], &mut step_count).unwrap_err();
+	assert_eq!(err, MigrationError::new(2, 1, "No downgrade is defined for this version"));
+	assert_eq!(file.version, 2);
+	
+	file.migrate(&[&rename_volume, &double_volume], &mut step_count).unwrap();
+	assert_eq!(file.to_str().0, "format 3\naudio.volume: 10\naudio.muted: true");
+}
+
+#[test]
+fn future_formats_are_not_updated() {
+	let (file, did_run_updaters, errors) = File::from_str::<()>("format 5\nvolume: 5", &[|_values, _args| {}], &mut ());
+	assert!(!did_run_updaters);
+	assert_eq!(file.version, 5);
+	assert_eq!(errors.len(), 1);
+	assert_eq!(errors[0].message, "Format 5 is newer than the latest supported format (2), so no updaters were run");
+}
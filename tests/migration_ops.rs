@@ -0,0 +1,60 @@
+mod common;
+
+use common::*;
+use easy_configuration_format::*;
+
+
+
+fn sum(values: &[Option<Value>]) -> Result<Value, String> {
+	let mut total = 0;
+	for value in values.iter().flatten() {
+		let Value::I64 (value) = value else {return Err(String::from("not an int"));};
+		total += value;
+	}
+	Ok(Value::I64 (total))
+}
+
+
+
+#[test]
+fn operations_keep_the_layout_in_sync() {
+	let mut file = parse("format 1\nvolume: 5\nold: true\ntimeout ms: 1500\nsize: \"3x4\"\nfps: 30");
+	let ops = [
+		MigrationOp::rename_key("volume", "audio.volume"),
+		MigrationOp::delete_key("old", "no longer used"),
+		MigrationOp::convert_value("timeout ms", |value| match value {
+			Value::I64 (ms) => Ok(Value::F64 (*ms as f64 / 1000.0)),
+			_ => Err(String::from("expected an int")),
+		}),
+		MigrationOp::split_key("size", |_value| Ok(vec!((String::from("width"), Value::I64 (3)), (String::from("height"), Value::I64 (4))))),
+		MigrationOp::update_default("fps", 30, 60),
+		MigrationOp::move_namespace("audio", "sound"),
+	];
+	let report = file.migrate(&[&ops], &mut ()).unwrap();
+	assert_eq!(report.to_version, 2);
+	assert_eq!(file.to_str().0, "format 2\nsound.volume: 5\n# removed setting (no longer used): old: true\ntimeout ms: 1.5\nwidth: 3\nheight: 4\nfps: 60");
+}
+
+#[test]
+fn merged_keys_take_the_first_position() {
+	let mut file = parse("format 1\na: 1\n# between\nb: 2\nc: 3");
+	MigrationOp::merge_keys(["b", "a"], "total", sum).apply(&mut file).unwrap();
+	assert_eq!(file.to_str().0, "format 1\ntotal: 3\n# between\nc: 3");
+	
+	// merging into one of the merged keys keeps its position
+	let mut file = parse("format 1\na: 1\n# between\nb: 2\nc: 3");
+	MigrationOp::merge_keys(["a", "b"], "b", sum).apply(&mut file).unwrap();
+	assert_eq!(file.to_str().0, "format 1\nb: 3\n# between\nc: 3");
+	assert_eq!(file.get_int("b"), Ok(3));
+	
+	let mut file = parse("format 1\na: 1\nb: 2");
+	assert!(MigrationOp::merge_keys(["a"], "b", sum).apply(&mut file).is_err());
+}
+
+#[test]
+fn failed_operations_name_the_key() {
+	let mut file = parse("format 1\na: \"x\"");
+	let err = file.migrate(&[&MigrationOp::merge_keys(["a"], "total", sum)], &mut ()).unwrap_err();
+	assert_eq!(err, MigrationError::new(1, 2, "Could not merge into \"total\": not an int"));
+	assert_eq!(file.to_str().0, "format 1\na: \"x\"");
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use common::*;
+use easy_configuration_format::*;
+
+
+
+fn rename_color(file: &mut File, context: &mut MigrationContext<()>) -> Result<(), MigrationError> {
+	MigrationOp::rename_key("color", "theme.color").migrate(file, context)
+}
+
+fn fail(_file: &mut File, context: &mut MigrationContext<()>) -> Result<(), MigrationError> {
+	Err(context.error("always fails"))
+}
+
+
+
+#[test]
+fn namespaces_migrate_independently() {
+	let mut file = parse("format 1\nvolume: 5\n# plugin settings\nplugin.color: \"red\"\nplugin.size: 2\nother.size: 3");
+	let migrations: [&dyn Migration<()>; 1] = [&rename_color];
+	let failing: [&dyn Migration<()>; 1] = [&fail];
+	let other = parse("format 1\nother.format: 1\nother.size: 3");
+	let mut other_file = other.clone();
+	let results = file.migrate_namespaces(&[VersionedNamespace::new("plugin", &migrations), VersionedNamespace::new("other", &failing)], &mut ());
+	assert!(results[0].is_ok());
+	assert_eq!(results[1].as_ref().unwrap_err().message, "(namespace \"other\") always fails");
+	assert_eq!(file.version, 1);
+	assert_eq!(file.namespace_version("plugin"), Some(2));
+	assert_eq!(file.to_str().0, "format 1\nvolume: 5\n# plugin settings\nplugin.format: 2\nplugin.theme.color: \"red\"\nplugin.size: 2\nother.size: 3");
+	
+	// already at the latest version
+	assert!(!file.migrate_namespace(&VersionedNamespace::new("plugin", &migrations), &mut ()).unwrap().did_run_updaters());
+	assert!(other_file.migrate_namespace(&VersionedNamespace::new("other", &failing), &mut ()).is_err());
+	assert_eq!(other_file, other);
+}
+
+#[test]
+fn namespaces_can_be_extracted_and_replaced() {
+	let mut file = parse("format 1\nplugin.format: 3\nplugin.a: 1\nmain: true\nplugin.b: 2");
+	let mut contents = file.extract_namespace("plugin");
+	assert_eq!(contents.version, 3);
+	assert_eq!(contents.to_str().0, "format 3\na: 1\nb: 2");
+	
+	contents.values.insert(String::from("c"), Value::I64 (3));
+	contents.layout.push(LayoutEntry::Key (String::from("c")));
+	contents.version = 4;
+	file.replace_namespace("plugin", &contents);
+	assert_eq!(file.to_str().0, "format 1\nplugin.format: 4\nplugin.a: 1\nplugin.b: 2\nplugin.c: 3\nmain: true");
+}
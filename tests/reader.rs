@@ -0,0 +1,28 @@
+mod common;
+
+use common::*;
+use easy_configuration_format::*;
+
+
+
+#[test]
+fn every_error_is_collected_with_line_numbers() {
+	let file = parse("format 1\nthreads: \"four\"\nname: \"app\"\n\nscale: true");
+	let mut reader = file.reader();
+	assert_eq!(reader.get_int("threads", 4), 4);
+	assert_eq!(reader.get_str("name", "default"), "app");
+	assert_eq!(reader.get_float("scale", 1.0), 1.0);
+	assert!(!reader.get_bool("missing", false));
+	let errors = reader.finish();
+	assert_eq!(errors.iter().map(|err| err.line).collect::<Vec<_>>(), [Some(2), Some(5), None]);
+	assert!(matches!(errors[2].error, RetrieveSettingError::Missing { .. }));
+	assert_eq!(errors[0].to_string(), "Invalid setting at line 2: Setting 'threads' was expected to be of type 'Int', but is of type 'String'");
+}
+
+#[test]
+fn no_errors_for_valid_files() {
+	let file = parse("format 1\nthreads: 8");
+	let mut reader = SettingsReader::new(&file);
+	assert_eq!(reader.get::<u16>("threads", 1), 8);
+	assert!(reader.errors().is_empty());
+}
@@ -0,0 +1,36 @@
+mod common;
+
+use common::*;
+use easy_configuration_format::*;
+
+
+
+#[test]
+fn invalid_values_are_reset_and_kept_as_comments() {
+	let mut file = parse("format 1\n# worker threads\nthreads: \"many\"\nname: \"app\"");
+	let (threads, repair) = file.get_or_repair("threads", 4i64);
+	assert_eq!(threads, 4);
+	let repair = repair.unwrap();
+	assert_eq!(repair.original, Some(Value::String (String::from("many"))));
+	assert_eq!(repair.replacement, Value::I64 (4));
+	assert_eq!(file.to_str().0, "format 1\n# worker threads\n# invalid value (reset to default): threads: \"many\"\nthreads: 4\nname: \"app\"");
+	
+	let (name, repair) = file.get_or_repair("name", String::from("default"));
+	assert_eq!(name, "app");
+	assert!(repair.is_none());
+}
+
+#[test]
+fn repairs_can_be_collected_into_a_list() {
+	let mut file = parse("format 1\nthreads: \"many\"");
+	let mut repairs = vec!();
+	let (_threads, repair) = file.get_or_repair("threads", 4i64);
+	repairs.extend(repair);
+	let (_scale, repair) = file.get_or_repair("scale", 1.5f64);
+	repairs.extend(repair);
+	assert_eq!(repairs.len(), 2);
+	assert_eq!(repairs[1].original, None);
+	assert_eq!(repairs[1].to_string(), "Setting 'scale' was missing and has been set to 1.5");
+	assert_eq!(file.get_float("scale"), Ok(1.5));
+	assert_eq!(file.line_of("scale"), Some(4));
+}
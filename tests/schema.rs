@@ -0,0 +1,91 @@
+mod common;
+
+use common::*;
+use easy_configuration_format::*;
+
+
+
+fn volume_schema() -> Schema {
+	Schema::new()
+		.entry(SchemaEntry::new("volume", ValueType::Int).required().range(0.0, 100.0))
+		.entry(SchemaEntry::new("log level", ValueType::String).allowed(["debug", "info"]).default("info").description("How much to log"))
+		.entry(SchemaEntry::new("name", ValueType::String).pattern("app-*"))
+}
+
+
+
+#[test]
+fn validate_reports_every_problem() {
+	let file = parse("format 1\nvolume: 150\nlog level: \"loud\"\nname: \"tool\"");
+	let violations = volume_schema().validate(&file);
+	assert_eq!(violations.len(), 3);
+	assert_eq!(violations[0].kind, ViolationKind::AboveMaximum { max: 100.0 });
+	assert_eq!(violations[0].line, Some(2));
+	assert_eq!(violations[0].value, Some(Value::I64 (150)));
+	assert_eq!(violations[1].kind, ViolationKind::NotAllowed { allowed: vec!(Value::String (String::from("debug")), Value::String (String::from("info"))) });
+	assert_eq!(violations[2].kind, ViolationKind::PatternMismatch { pattern: String::from("app-*") });
+	assert_eq!(violations[0].to_string(), "Invalid setting 'volume' at line 2: value cannot be greater than 100");
+}
+
+#[test]
+fn validate_reports_missing_and_wrong_types() {
+	let file = parse("format 1\nname: 5");
+	let violations = volume_schema().validate(&file);
+	assert_eq!(violations.len(), 2);
+	assert_eq!(violations[0].kind, ViolationKind::Missing);
+	assert_eq!(violations[0].line, None);
+	assert_eq!(violations[1].kind, ViolationKind::WrongType { expected: ValueType::String, encountered: "Int" });
+	
+	let file = parse("format 1\nvolume: 50\nname: \"app-1\"");
+	assert!(volume_schema().validate(&file).is_empty());
+}
+
+#[test]
+fn apply_defaults_adds_missing_settings_with_descriptions() {
+	let mut file = parse("format 1\nvolume: 50");
+	let added = volume_schema().apply_defaults(&mut file);
+	assert_eq!(added, vec!(String::from("log level")));
+	assert_eq!(file.to_str().0, "format 1\nvolume: 50\n# How much to log\nlog level: \"info\"");
+	
+	assert!(volume_schema().apply_defaults(&mut file).is_empty());
+}
+
+#[test]
+fn schema_files_round_trip() {
+	let file = volume_schema().to_file();
+	let (schema, errors) = Schema::from_file(&file);
+	assert!(errors.is_empty());
+	assert_eq!(schema, volume_schema());
+	
+	let (schema, errors) = Schema::from_file(&parse("format 1\nvolume.type: \"int\"\nvolume.min: 0\nvolume.max: 1.5\nlevel.allowed.1: \"b\"\nlevel.allowed.0: \"a\""));
+	assert!(errors.is_empty());
+	assert_eq!(schema.get("volume").unwrap().max, Some(1.5));
+	assert_eq!(schema.get("level").unwrap().allowed, vec!(Value::String (String::from("a")), Value::String (String::from("b"))));
+}
+
+#[test]
+fn invalid_schema_attributes_are_skipped() {
+	let (schema, errors) = Schema::from_file(&parse("format 1\nvolume.type: \"integer\"\nvolume.color: \"red\"\nvolume.required: 1"));
+	assert_eq!(schema.entries.len(), 1);
+	assert_eq!(schema.entries[0].value_type, ValueType::Any);
+	assert!(!schema.entries[0].required);
+	assert_eq!(errors.len(), 3);
+	assert_eq!(errors[0].line, Some(2));
+	assert!(errors[1].message.contains("Unknown attribute \"color\""));
+}
+
+#[test]
+fn generated_files_hold_defaults_and_placeholders() {
+	let schema = Schema::new()
+		.entry(SchemaEntry::new("volume", ValueType::Int).range(0.0, 100.0).default(50).description("Master volume"))
+		.entry(SchemaEntry::new("audio.device", ValueType::String).required())
+		.entry(SchemaEntry::new("audio.note", ValueType::Any).required());
+	let file = schema.generate_file(2);
+	assert_eq!(file.version, 2);
+	assert_eq!(file.to_str().0, "format 2\n# Master volume\n# allowed range: 0 to 100\nvolume: 50\n\n# audio.device: (required string, please set a value and remove the '#')\naudio.note: empty");
+	
+	let violations = schema.validate(&file);
+	assert_eq!(violations.len(), 1);
+	assert_eq!(violations[0].key, "audio.device");
+	assert_eq!(violations[0].kind, ViolationKind::Missing);
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::*;
+use easy_configuration_format::*;
+use std::path::PathBuf;
+
+
+
+#[test]
+fn ints_are_checked_for_overflow() {
+	let mut file = parse("format 1\nsmall: 200\nbig: 300\nhuge: 9223372036854775807");
+	file.values.insert(String::from("negative"), Value::I64 (-1));
+	assert_eq!(file.get::<u8>("small"), Ok(200));
+	assert_eq!(file.get::<i64>("huge"), Ok(i64::MAX));
+	assert_eq!(file.get::<i8>("negative"), Ok(-1));
+	assert_eq!(file.get::<u8>("big"), Err(RetrieveSettingError::OutOfRange { key: String::from("big"), value: String::from("300"), target: String::from("u8") }));
+	assert!(matches!(file.get::<u32>("negative"), Err(RetrieveSettingError::OutOfRange { .. })));
+	assert!(matches!(file.get::<i32>("huge"), Err(RetrieveSettingError::OutOfRange { .. })));
+}
+
+#[test]
+fn other_types_are_converted() {
+	let file = parse("format 1\nratio: 0.5\nname: \"example\"\npath: \"/tmp/x\"\nletter: \"a\"\nword: \"ab\"\nflag: true");
+	assert_eq!(file.get::<f32>("ratio"), Ok(0.5));
+	assert_eq!(file.get::<f64>("ratio"), Ok(0.5));
+	assert_eq!(file.get::<String>("name"), Ok(String::from("example")));
+	assert_eq!(file.get::<PathBuf>("path"), Ok(PathBuf::from("/tmp/x")));
+	assert_eq!(file.get::<char>("letter"), Ok('a'));
+	assert!(file.get::<char>("word").is_err());
+	assert_eq!(file.get::<bool>("flag"), Ok(true));
+	assert!(matches!(file.get::<bool>("name"), Err(RetrieveSettingError::WrongSingularType { .. })));
+	assert!(matches!(file.get::<i64>("missing"), Err(RetrieveSettingError::Missing { .. })));
+}
+
+#[test]
+fn options_treat_empty_as_none() {
+	let file = parse("format 1\nlimit: empty\ncount: 3");
+	assert_eq!(file.get::<Option<i64>>("limit"), Ok(None));
+	assert_eq!(file.get::<Option<i64>>("count"), Ok(Some(3)));
+}
+
+#[test]
+fn vecs_are_read_from_array_namespaces() {
+	let file = parse("format 1\nitems.0: 1\nitems.1: 2\nitems.2: 3\nnone: empty");
+	assert_eq!(file.get::<Vec<i64>>("items"), Ok(vec!(1, 2, 3)));
+	assert_eq!(file.get::<Vec<i64>>("none"), Ok(vec!()));
+}
+
+#[test]
+fn into_value_round_trips() {
+	assert_eq!(5u8.into_value(), Value::I64 (5));
+	assert_eq!(1.5f32.into_value(), Value::F64 (1.5));
+	assert_eq!("hi".into_value(), Value::String (String::from("hi")));
+	assert_eq!(None::<i64>.into_value(), Value::Empty);
+	let mut file = parse("format 1\nint: empty");
+	file.values.insert(String::from("int"), 5u8.into_value());
+	assert_eq!(file.get::<u8>("int"), Ok(5));
+}
@@ -0,0 +1,27 @@
+mod common;
+
+use common::*;
+use easy_configuration_format::*;
+
+
+
+#[test]
+fn unknown_keys_are_found_with_suggestions() {
+	let file = parse("format 1\nvolume: 5\nvolumn: 6\nservers.a.name: \"x\"\ncompletely different: 1");
+	let unknown_keys = file.find_unknown_keys(["volume", "servers.*.name", "colors"]);
+	assert_eq!(unknown_keys.len(), 2);
+	assert_eq!(unknown_keys[0], UnknownKey { key: String::from("volumn"), line: Some(3), suggestion: Some(String::from("volume")) });
+	assert_eq!(unknown_keys[1].key, "completely different");
+	assert_eq!(unknown_keys[1].suggestion, None);
+	assert_eq!(unknown_keys[0].to_string(), "Unknown setting 'volumn' at line 3, did you mean 'volume'?");
+}
+
+#[test]
+fn unknown_keys_are_only_marked_once() {
+	let mut file = parse("format 1\nvolumn: 6");
+	let schema = Schema::new().entry(SchemaEntry::new("volume", ValueType::Int));
+	let unknown_keys = schema.find_unknown_keys(&file);
+	file.mark_unknown_keys(&unknown_keys);
+	file.mark_unknown_keys(&unknown_keys);
+	assert_eq!(file.to_str().0, "format 1\n# unknown setting, did you mean \"volume\"?\nvolumn: 6");
+}